@@ -0,0 +1,74 @@
+//! Benchmarks every day's generator and both parts against the real inputs.
+//!
+//! Run with `cargo bench`, then `cargo run --example readme_tables` to copy the results into
+//! the README. Besides criterion's own reports, a summary of the mean times is written to
+//! `target/criterion/summary.json`.
+
+use advent_of_code_2024::solution::SOLUTIONS;
+use advent_of_code_2024::solvers::input_path;
+use criterion::{black_box, Criterion};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fs};
+
+const STAGES: [&str; 3] = ["generator", "part1", "part2"];
+
+fn bench_days(c: &mut Criterion) {
+    for entry in SOLUTIONS {
+        let Ok(input) = fs::read_to_string(input_path(entry.day)) else {
+            continue;
+        };
+        let input = input.trim_end_matches('\n');
+        let parsed = entry.parse(input).unwrap();
+
+        let mut group = c.benchmark_group(format!("day{}", entry.day));
+        group.bench_function("generator", |b| b.iter(|| entry.parse(black_box(input))));
+        group.bench_function("part1", |b| b.iter(|| parsed.solve(black_box(1))));
+        group.bench_function("part2", |b| b.iter(|| parsed.solve(black_box(2))));
+        group.finish();
+    }
+}
+
+/// Where criterion keeps its results.
+fn criterion_dir() -> PathBuf {
+    match env::var_os("CRITERION_HOME") {
+        Some(home) => PathBuf::from(home),
+        None => PathBuf::from(env::var_os("CARGO_TARGET_DIR").unwrap_or_else(|| "target".into()))
+            .join("criterion"),
+    }
+}
+
+/// Collects the latest mean time of every benchmark into one file.
+fn write_summary() {
+    let dir = criterion_dir();
+    let mut results = Vec::new();
+    for entry in SOLUTIONS {
+        for stage in STAGES {
+            let path = dir
+                .join(format!("day{}", entry.day))
+                .join(stage)
+                .join("new/estimates.json");
+            let Ok(contents) = fs::read_to_string(path) else {
+                continue;
+            };
+            let estimates: Value = serde_json::from_str(&contents).unwrap();
+            if let Some(mean) = estimates["mean"]["point_estimate"].as_f64() {
+                results.push(json!({ "day": entry.day, "stage": stage, "mean_ns": mean }));
+            }
+        }
+    }
+    let path = dir.join("summary.json");
+    fs::write(&path, serde_json::to_string_pretty(&results).unwrap()).unwrap();
+    println!("Wrote {}", path.display());
+}
+
+fn main() {
+    let mut c = Criterion::default()
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(3))
+        .configure_from_args();
+    bench_days(&mut c);
+    c.final_summary();
+    write_summary();
+}
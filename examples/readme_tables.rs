@@ -0,0 +1,98 @@
+//! Rewrites the README's Generator/Runner tables from the benchmark summary.
+//!
+//! Usage: `cargo run --example readme_tables [SUMMARY] [README]`, defaulting to
+//! `target/criterion/summary.json` and `README.md`. Days without results keep a `?`.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::{env, fs, process};
+
+const HEADER: &str = "| Generator  |   Runner   |";
+const DAYS: u32 = 25;
+const WIDTH: usize = 12;
+
+/// Formats nanoseconds the way the tables always have, e.g. `54.553 µs`.
+fn format_time(ns: f64) -> String {
+    let (value, unit) = if ns < 1e3 {
+        (ns, "ns")
+    } else if ns < 1e6 {
+        (ns / 1e3, "µs")
+    } else if ns < 1e9 {
+        (ns / 1e6, "ms")
+    } else {
+        (ns / 1e9, "s")
+    };
+    format!("{:.3} {}", value, unit)
+}
+
+fn cell(text: &str) -> String {
+    format!("{:^width$}", text, width = WIDTH)
+}
+
+/// Replaces the rows of the `n`th timing table (0 for part 1, 1 for part 2).
+fn rewrite_table(readme: &str, n: usize, rows: &[String]) -> Result<String, String> {
+    let lines: Vec<&str> = readme.lines().collect();
+    let start = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim() == HEADER)
+        .nth(n)
+        .map(|(i, _)| i + 2)
+        .ok_or_else(|| format!("README has no timing table {}", n + 1))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .take_while(|line| line.starts_with('|'))
+            .count();
+
+    let mut out: Vec<&str> = lines[..start].to_vec();
+    out.extend(rows.iter().map(String::as_str));
+    out.extend(&lines[end..]);
+    Ok(out.join("\n") + "\n")
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let summary_path = args
+        .first()
+        .map_or("target/criterion/summary.json", String::as_str);
+    let readme_path = args.get(1).map_or("README.md", String::as_str);
+
+    let summary = fs::read_to_string(summary_path).unwrap_or_else(|e| {
+        eprintln!("error: {}: {} (run `cargo bench` first)", summary_path, e);
+        process::exit(1);
+    });
+    let summary: Vec<Value> = serde_json::from_str(&summary).unwrap_or_else(|e| {
+        eprintln!("error: {}: {}", summary_path, e);
+        process::exit(1);
+    });
+    let times: HashMap<(u64, &str), f64> = summary
+        .iter()
+        .filter_map(|result| {
+            Some((
+                (result["day"].as_u64()?, result["stage"].as_str()?),
+                result["mean_ns"].as_f64()?,
+            ))
+        })
+        .collect();
+
+    let mut readme = fs::read_to_string(readme_path).unwrap();
+    for (n, part) in ["part1", "part2"].into_iter().enumerate() {
+        let rows: Vec<String> = (1..=DAYS as u64)
+            .map(|day| {
+                let time = |stage| {
+                    times
+                        .get(&(day, stage))
+                        .map_or_else(|| "?".to_string(), |&ns| format_time(ns))
+                };
+                format!("|{}|{}|", cell(&time("generator")), cell(&time(part)))
+            })
+            .collect();
+        readme = rewrite_table(&readme, n, &rows).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        });
+    }
+    fs::write(readme_path, readme).unwrap();
+    println!("Updated {}", readme_path);
+}
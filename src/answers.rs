@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Default location of the answers file, relative to the crate root.
+pub const ANSWERS_PATH: &str = "answers/2024.toml";
+
+/// Known-correct answers for the real inputs, keyed by (day, part).
+///
+/// Stored as a small TOML subset: one `[dayN]` table per day holding `partN = answer`,
+/// where the answer is an integer or a quoted string. Strings escape only `\\`, `\"` and
+/// newlines, as `\n`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Answers(BTreeMap<(u32, u32), String>);
+
+/// A solver result compared against the stored answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut answers = Answers::default();
+        let mut day = None;
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            let error = |msg: &str| format!("line {}: {}", i + 1, msg);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // Table header
+            if let Some(header) = line.strip_prefix('[') {
+                let name = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| error("unclosed table header"))?;
+                day = Some(number(name.trim(), "day").ok_or_else(|| error("expected [dayN]"))?);
+                continue;
+            }
+
+            // Key/value pair
+            let day = day.ok_or_else(|| error("answer outside of a [dayN] table"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected partN = answer"))?;
+            let part = number(key.trim(), "part").ok_or_else(|| error("expected partN"))?;
+            let value = value.trim();
+            let answer = match value.strip_prefix('"') {
+                Some(quoted) => unquote(quoted).map_err(error)?,
+                None if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) => {
+                    value.to_string()
+                }
+                None => return Err(error("answer must be an integer or a quoted string")),
+            };
+            if answers.0.insert((day, part), answer).is_some() {
+                return Err(error("duplicate answer"));
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Reads the answers file; a missing file counts as no answers yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(path, self.to_string()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u32, part: u32, answer: impl Into<String>) {
+        self.0.insert((day, part), answer.into());
+    }
+
+    pub fn check(&self, day: u32, part: u32, answer: &str) -> Check {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Check::Correct,
+            Some(expected) => Check::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Check::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# Known-correct answers for input/2024/dayN.txt")?;
+        let mut last_day = None;
+        for (&(day, part), answer) in &self.0 {
+            if last_day != Some(day) {
+                writeln!(f, "\n[day{}]", day)?;
+                last_day = Some(day);
+            }
+            if answer.chars().all(|c| c.is_ascii_digit()) && !answer.is_empty() {
+                writeln!(f, "part{} = {}", part, answer)?;
+            } else {
+                writeln!(f, "part{} = {}", part, quote(answer))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes a string answer in quotes, escaping only what would end or break the string.
+fn quote(answer: &str) -> String {
+    let mut quoted = String::from("\"");
+    for ch in answer.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reads a string answer written by [`quote`], given everything after its opening quote.
+fn unquote(quoted: &str) -> Result<String, &'static str> {
+    let mut answer = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if chars.as_str().is_empty() => return Ok(answer),
+            '"' => return Err("unexpected text after string"),
+            '\\' => match chars.next() {
+                Some('"') => answer.push('"'),
+                Some('\\') => answer.push('\\'),
+                Some('n') => answer.push('\n'),
+                _ => return Err("invalid escape in string"),
+            },
+            ch => answer.push(ch),
+        }
+    }
+    Err("unterminated string")
+}
+
+/// Parses names like `day7` into their number.
+fn number(name: &str, prefix: &str) -> Option<u32> {
+    name.strip_prefix(prefix)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::{day_parts, input_path, solve};
+    use indoc::indoc;
+
+    #[test]
+    fn parse_and_write() {
+        let input = indoc! {
+            r#"# comment
+
+            [day1]
+            part1 = 11
+            part2 = 31
+
+            [day17]
+            part1 = "4,6,3,5,6,3,5,2,1,0"
+            "#
+        };
+        let answers = Answers::parse(input).unwrap();
+        assert_eq!(answers.get(1, 2), Some("31"));
+        assert_eq!(answers.get(17, 1), Some("4,6,3,5,6,3,5,2,1,0"));
+        assert_eq!(answers.get(17, 2), None);
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers);
+
+        assert_eq!(answers.check(1, 1, "11"), Check::Correct);
+        assert_eq!(
+            answers.check(1, 1, "12"),
+            Check::Wrong {
+                expected: "11".to_string()
+            }
+        );
+        assert_eq!(answers.check(2, 1, "12"), Check::Unknown);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Answers::parse("part1 = 1").unwrap_err(),
+            "line 1: answer outside of a [dayN] table"
+        );
+        assert_eq!(
+            Answers::parse("[day1]\npart1 = 1\npart1 = 2").unwrap_err(),
+            "line 3: duplicate answer"
+        );
+        assert!(Answers::parse("[week1]").is_err());
+        assert!(Answers::parse("[day1]\npart1 = abc").is_err());
+        assert!(Answers::parse("[day1]\npart1 = \"abc").is_err());
+        assert_eq!(
+            Answers::parse("[day1]\npart1 = \"a\\tb\"").unwrap_err(),
+            "line 2: invalid escape in string"
+        );
+        assert_eq!(
+            Answers::parse("[day1]\npart1 = \"a\"b\"").unwrap_err(),
+            "line 2: unexpected text after string"
+        );
+    }
+
+    #[test]
+    fn string_answers_round_trip() {
+        let mut answers = Answers::default();
+        for (part, answer) in ["C:\\dir", "say \"hi\"", "héllo ✓", "two\nlines", "", "\\\""]
+            .into_iter()
+            .enumerate()
+        {
+            answers.insert(1, part as u32, answer);
+        }
+        let written = answers.to_string();
+        assert!(written.contains(r#"part0 = "C:\\dir""#), "{}", written);
+        assert!(written.contains("part2 = \"héllo ✓\""), "{}", written);
+        assert_eq!(Answers::parse(&written).unwrap(), answers);
+    }
+
+    /// Runs every registered solver against its real input and diffs against the stored answers.
+    #[test]
+    fn stored_answers() {
+        let answers = Answers::load(ANSWERS_PATH).unwrap();
+        let mut failures = Vec::new();
+        for (day, part, entry) in day_parts() {
+            let Some(expected) = answers.get(day, part) else {
+                continue;
+            };
+            let input = fs::read_to_string(input_path(day)).unwrap();
+            match solve(entry, part, &input).answer() {
+                Some(answer) if answer == expected => {}
+                answer => failures.push(format!(
+                    "day {} part {}: expected {}, got {:?}",
+                    day, part, expected, answer
+                )),
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,96 @@
+extern crate advent_of_code_2024;
+
+use advent_of_code_2024::gen::generate;
+use std::env;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: gen --day <N> [OPTIONS]
+
+Prints a generated puzzle input of arbitrary size to stdout.
+
+Options:
+  -d, --day <N>      Day to generate an input for
+  -n, --size <N>     Number of lines, grid side or files, depending on the day (default 1000)
+  -s, --seed <N>     Random seed (default 0)
+  -a, --answer       Print the planted part 1 answer to stderr
+  -h, --help         Print this help
+
+Example: cargo run --release --bin gen -- --day 5 --size 100000 | cargo run --release -- --day 5 --input -";
+
+struct Options {
+    day: u32,
+    size: usize,
+    seed: u64,
+    answer: bool,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match generate(options.day, options.size, options.seed) {
+        Some(generated) => {
+            print!("{}", generated.input);
+            if !generated.input.ends_with('\n') {
+                println!();
+            }
+            if options.answer {
+                eprintln!("{}", generated.answer);
+            }
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("error: no generator for day {}", options.day);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Parses the command line; `None` means help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut day = None;
+    let mut options = Options {
+        day: 0,
+        size: 1000,
+        seed: 0,
+        answer: false,
+    };
+    while let Some(arg) = args.next() {
+        // Accept both `--day 5` and `--day=5`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+        let number = |value: String| {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("invalid {} {:?}", flag.trim_start_matches('-'), value))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-d" | "--day" => day = Some(number(value()?)? as u32),
+            "-n" | "--size" => options.size = number(value()?)? as usize,
+            "-s" | "--seed" => options.seed = number(value()?)?,
+            "-a" | "--answer" => options.answer = true,
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+    options.day = day.ok_or("--day is required")?;
+    Ok(Some(options))
+}
@@ -1,3 +1,5 @@
+use crate::solution::Solution;
+
 #[aoc_generator(day1)]
 fn parse(input: &str) -> (Vec<u32>, Vec<u32>) {
     let mut left = Vec::new();
@@ -24,7 +26,7 @@ fn day1_part1((left, right): &(Vec<u32>, Vec<u32>)) -> u32 {
     // Calculate the total distance
     left.iter()
         .zip(right.iter())
-        .map(|(l, r)| (*l as i32 - *r as i32).abs() as u32)
+        .map(|(l, r)| l.abs_diff(*r))
         .sum()
 }
 #[aoc(day1, part2)]
@@ -42,10 +44,33 @@ fn day1_part2((left, right): &(Vec<u32>, Vec<u32>)) -> u32 {
     left.iter().map(|&num| num * frequency[num as usize]).sum()
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = (Vec<u32>, Vec<u32>);
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        day1_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        day1_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn part1_example() {
@@ -68,4 +93,32 @@ mod tests {
     3   3"};
         assert_eq!(day1_part2(&parse(SAMPLE)), 31);
     }
+
+    /// Pairs up the smallest remaining numbers one at a time.
+    fn reference_distance(left: &[u32], right: &[u32]) -> u32 {
+        let (mut left, mut right) = (left.to_vec(), right.to_vec());
+        let mut total = 0;
+        while let (Some(&l), Some(&r)) = (left.iter().min(), right.iter().min()) {
+            total += l.abs_diff(r);
+            left.remove(left.iter().position(|&x| x == l).unwrap());
+            right.remove(right.iter().position(|&x| x == r).unwrap());
+        }
+        total
+    }
+
+    fn reference_similarity(left: &[u32], right: &[u32]) -> u32 {
+        left.iter()
+            .map(|&l| l * right.iter().filter(|&&r| r == l).count() as u32)
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(pairs in prop::collection::vec((0u32..20, 0u32..20), 0..30)) {
+            let (left, right): (Vec<u32>, Vec<u32>) = pairs.into_iter().unzip();
+            let lists = (left.clone(), right.clone());
+            prop_assert_eq!(day1_part1(&lists), reference_distance(&left, &right));
+            prop_assert_eq!(day1_part2(&lists), reference_similarity(&left, &right));
+        }
+    }
 }
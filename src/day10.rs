@@ -1,180 +1,1381 @@
-use std::collections::{HashSet, VecDeque};
+use crate::solution::Solution;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::ops::RangeInclusive;
 
+/// Height of a cell, or `None` for an impassable cell.
+pub type Height = Option<u8>;
+
+/// Parses a height map. Each character is a cell: a hex digit for its height, or `.` for an
+/// impassable cell. For heights beyond 15, separate the cells of a row with spaces or commas
+/// and write each height in decimal; if any row does, every row is read that way. Every row
+/// must have the same number of cells.
 #[aoc_generator(day10)]
-fn parse(input: &str) -> Vec<Vec<u8>> {
-    input
+pub fn parse(input: &str) -> Result<Vec<Vec<Height>>, String> {
+    // The format is chosen for the whole map, so rows can't be read in different encodings
+    let separated = input.lines().any(|line| line.trim().contains([' ', ',']));
+    let map = input
         .lines()
-        .map(|line| {
-            line.trim()
-                .chars()
-                .map(|c| c.to_digit(10).expect("Invalid character") as u8)
-                .collect()
+        .enumerate()
+        .map(|(r, line)| {
+            let line = line.trim();
+            let invalid = |c: usize, cell: &str| {
+                format!(
+                    "invalid height {:?} at row {}, column {}",
+                    cell,
+                    r + 1,
+                    c + 1
+                )
+            };
+
+            if separated {
+                // One decimal height per token
+                line.split([' ', ','])
+                    .filter(|token| !token.is_empty())
+                    .enumerate()
+                    .map(|(c, token)| match token {
+                        "." => Ok(None),
+                        _ => token.parse().map(Some).map_err(|_| invalid(c, token)),
+                    })
+                    .collect()
+            } else {
+                // One hex digit per character
+                line.chars()
+                    .enumerate()
+                    .map(|(c, ch)| match ch {
+                        '.' => Ok(None),
+                        _ => ch
+                            .to_digit(16)
+                            .map(|h| Some(h as u8))
+                            .ok_or_else(|| invalid(c, &ch.to_string())),
+                    })
+                    .collect()
+            }
         })
-        .collect()
+        .collect::<Result<_, _>>()?;
+    // The layered search indexes every row by the first row's width
+    crate::grid::rectangular(map)
 }
 
 #[aoc(day10, part1)]
-fn day10_part1(map: &Vec<Vec<u8>>) -> usize {
-    let rows = map.len();
-    if rows == 0 {
-        return 0;
+fn day10_part1(map: &[Vec<Height>]) -> usize {
+    trail_scores(map, &TrailRules::default())
+}
+
+#[aoc(day10, part2)]
+fn day10_part2(map: &[Vec<Height>]) -> usize {
+    trail_ratings(map, &TrailRules::default()).expect("climbing trails cannot loop")
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Vec<Vec<Height>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
     }
-    let cols = map[0].len();
 
-    // Identify all trailheads (positions with height 0)
-    let mut trailheads = Vec::new();
-    for r in 0..rows {
-        for c in 0..cols {
-            if map[r][c] == 0 {
-                trailheads.push((r, c));
-            }
-        }
+    fn part1(input: &Self::Input) -> usize {
+        day10_part1(input)
     }
 
-    let mut sum_scores = 0;
+    fn part2(input: &Self::Input) -> usize {
+        day10_part2(input)
+    }
+}
+
+/// Which neighbouring cells a trail may step to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Up, down, left and right.
+    Four,
+    /// Diagonals as well.
+    Eight,
+}
 
-    for &(trail_r, trail_c) in &trailheads {
-        // BFS setup
-        let mut visited = vec![vec![false; cols]; rows];
-        let mut queue = VecDeque::new();
-        let mut reachable_nines = HashSet::new();
+impl Neighbourhood {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Neighbourhood::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
 
-        // Initialize BFS
-        queue.push_back((trail_r, trail_c, 0)); // (row, col, current_height)
-        visited[trail_r][trail_c] = true;
+/// What counts as a hiking trail. The default is the puzzle's: start at 0, climb by exactly
+/// one per step in the four cardinal directions, end at 9.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrailRules {
+    /// Allowed height change per step, e.g. `-1..=-1` for descending trails.
+    pub step: RangeInclusive<i16>,
+    pub neighbourhood: Neighbourhood,
+    /// Height every trail starts at.
+    pub start: u8,
+    /// Height every trail ends at. A trail stops at the first cell of this height it reaches.
+    pub end: u8,
+    /// Whether a trail may pass through the same cell more than once. This only matters
+    /// when steps can keep the height level or go both up and down.
+    pub revisit: bool,
+}
 
-        while let Some((r, c, current_height)) = queue.pop_front() {
-            if map[r][c] == 9 {
-                reachable_nines.insert((r, c));
-                continue;
-            }
+impl Default for TrailRules {
+    fn default() -> Self {
+        TrailRules {
+            step: 1..=1,
+            neighbourhood: Neighbourhood::Four,
+            start: 0,
+            end: 9,
+            revisit: false,
+        }
+    }
+}
 
-            let next_height = current_height + 1;
+impl TrailRules {
+    /// Whether every step changes the height in the same direction, so no trail can loop.
+    fn is_monotonic(&self) -> bool {
+        *self.step.start() > 0 || *self.step.end() < 0
+    }
 
-            // Explore all four directions
-            let directions = [(-1isize, 0isize), (1, 0), (0, -1), (0, 1)];
-            for &(dr, dc) in &directions {
+    /// Cells a trail at `(r, c)` may step to next. Trails do not continue past their end.
+    fn moves<'a>(
+        &'a self,
+        map: &'a [Vec<Height>],
+        (r, c): (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), (isize, isize))> + 'a {
+        // Impassable cells lead nowhere
+        let height = map[r][c];
+        let at_end = height == Some(self.end);
+        self.neighbourhood
+            .offsets()
+            .iter()
+            .filter(move |_| !at_end)
+            .filter_map(move |&(dr, dc)| {
                 let nr = r as isize + dr;
                 let nc = c as isize + dc;
-
                 // Check bounds
-                if nr >= 0 && nr < rows as isize && nc >= 0 && nc < cols as isize {
-                    let nr_usize = nr as usize;
-                    let nc_usize = nc as usize;
-                    if map[nr_usize][nc_usize] == next_height && !visited[nr_usize][nc_usize] {
-                        visited[nr_usize][nc_usize] = true;
-                        queue.push_back((nr_usize, nc_usize, next_height));
+                if nr < 0 || nc < 0 {
+                    return None;
+                }
+                let next = (*map.get(nr as usize)?.get(nc as usize)?)?;
+                let delta = next as i16 - height? as i16;
+                self.step
+                    .contains(&delta)
+                    .then_some(((nr as usize, nc as usize), (dr, dc)))
+            })
+    }
+}
+
+/// Longest trail, in steps, that [`trail_scores`] handles in its single layered pass. That
+/// pass keeps `(2n + 1)²` bits for every cell of the last few heights, where `n` is the
+/// longest trail, so longer trails are scored by a search from each trailhead instead.
+pub const MAX_LAYERED_TRAIL: usize = 32;
+
+/// Sum of trailhead scores: the number of distinct end cells each trailhead can reach.
+/// Trails longer than [`MAX_LAYERED_TRAIL`] steps are slower to score but use little memory.
+pub fn trail_scores(map: &[Vec<Height>], rules: &TrailRules) -> usize {
+    let radius = max_trail_length(rules);
+    if !rules.is_monotonic() || radius > MAX_LAYERED_TRAIL {
+        return trailheads(map, rules)
+            .map(|start| reachable_ends(map, rules, start).len())
+            .sum();
+    }
+
+    // A cell's score set is the union of the sets of the cells it can step to
+    let side = 2 * radius as isize + 1;
+    propagate(
+        map,
+        rules,
+        || Reach::end(radius),
+        || Reach::empty(radius),
+        |acc, next, (dr, dc)| acc.union_shifted(next, dr * side + dc),
+    )
+    .iter()
+    .map(Reach::len)
+    .sum()
+}
+
+/// Sum of trailhead ratings: the number of distinct trails from each trailhead, or `None`
+/// if revisiting cells lets some trail loop forever.
+pub fn trail_ratings(map: &[Vec<Height>], rules: &TrailRules) -> Option<usize> {
+    if rules.is_monotonic() {
+        // A cell's rating is the sum of the ratings of the cells it can step to
+        let ratings = propagate(map, rules, || 1, || 0, |acc, next, _| *acc += next);
+        Some(ratings.iter().sum())
+    } else if rules.revisit {
+        count_walks(map, rules)
+    } else {
+        let mut on_trail = HashSet::new();
+        Some(
+            trailheads(map, rules)
+                .map(|start| count_simple_trails(map, rules, start, &mut on_trail))
+                .sum(),
+        )
+    }
+}
+
+fn trailheads<'a>(
+    map: &'a [Vec<Height>],
+    rules: &'a TrailRules,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    map.iter().enumerate().flat_map(move |(r, row)| {
+        row.iter()
+            .enumerate()
+            .filter(move |&(_, &height)| height == Some(rules.start))
+            .map(move |(c, _)| (r, c))
+    })
+}
+
+/// Longest possible trail, in steps, under monotonic rules.
+fn max_trail_length(rules: &TrailRules) -> usize {
+    let min_step = rules.step.start().abs().min(rules.step.end().abs()) as usize;
+    (rules.start as usize).abs_diff(rules.end as usize) / min_step.max(1)
+}
+
+/// Computes a value for every trailhead in a single pass over the map under monotonic
+/// rules, from the end height back to the start height. Each end cell gets its own value
+/// from `at_end`, and every other cell starts `empty` and `combine`s the values of the
+/// cells it can step to, along with their (row, col) offset. Values for a height are
+/// dropped as soon as no remaining cell can step to it.
+fn propagate<T>(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    mut at_end: impl FnMut() -> T,
+    mut empty: impl FnMut() -> T,
+    mut combine: impl FnMut(&mut T, &T, (isize, isize)),
+) -> Vec<T> {
+    let cols = map.first().map_or(0, Vec::len);
+
+    // Heights a trail passes through, from its end back to its start
+    let heights: Vec<u8> = if rules.start <= rules.end {
+        (rules.start..=rules.end).rev().collect()
+    } else {
+        (rules.end..=rules.start).collect()
+    };
+
+    // Bucket cells by height
+    let mut layers: Vec<Vec<(usize, usize)>> = vec![Vec::new(); heights.len()];
+    for (r, row) in map.iter().enumerate() {
+        for (c, &height) in row.iter().enumerate() {
+            if let Some(i) = heights.iter().position(|&h| Some(h) == height) {
+                layers[i].push((r, c));
+            }
+        }
+    }
+
+    // A cell can only step to heights at most this many layers back
+    let reach_back = rules.step.start().abs().max(rules.step.end().abs()) as usize;
+
+    let mut values: Vec<Option<T>> = (0..map.len() * cols).map(|_| None).collect();
+    for (i, layer) in layers.iter().enumerate() {
+        for &(r, c) in layer {
+            let value = if i == 0 {
+                at_end()
+            } else {
+                let mut value = empty();
+                for ((nr, nc), offset) in rules.moves(map, (r, c)) {
+                    if let Some(next) = &values[nr * cols + nc] {
+                        combine(&mut value, next, offset);
                     }
                 }
+                value
+            };
+            values[r * cols + c] = Some(value);
+        }
+
+        // Nothing left to process steps to this far back any more
+        if let Some(stale) = i.checked_sub(reach_back) {
+            for &(r, c) in &layers[stale] {
+                values[r * cols + c] = None;
             }
         }
+    }
 
-        sum_scores += reachable_nines.len();
+    layers
+        .last()
+        .into_iter()
+        .flatten()
+        .filter_map(|&(r, c)| values[r * cols + c].take())
+        .collect()
+}
+
+/// End cells reachable from a trailhead, by breadth-first search.
+fn reachable_ends(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    start: (usize, usize),
+) -> HashSet<(usize, usize)> {
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut ends = HashSet::new();
+
+    while let Some((r, c)) = queue.pop_front() {
+        if map[r][c] == Some(rules.end) {
+            ends.insert((r, c));
+        }
+        for (next, _) in rules.moves(map, (r, c)) {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
     }
 
-    sum_scores
+    ends
 }
 
-#[aoc(day10, part2)]
-fn day10_part2(map: &Vec<Vec<u8>>) -> usize {
-    let rows = map.len();
-    if rows == 0 {
-        return 0;
+/// Counts trails from `pos` that never pass through the same cell twice, by backtracking.
+fn count_simple_trails(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    pos: (usize, usize),
+    on_trail: &mut HashSet<(usize, usize)>,
+) -> usize {
+    if map[pos.0][pos.1] == Some(rules.end) {
+        return 1;
     }
-    let cols = map[0].len();
 
-    let mut trailheads = Vec::new();
-    for r in 0..rows {
-        for c in 0..cols {
-            if map[r][c] == 0 {
-                trailheads.push((r, c));
-            }
+    on_trail.insert(pos);
+    let mut total = 0;
+    for (next, _) in rules.moves(map, pos) {
+        if !on_trail.contains(&next) {
+            total += count_simple_trails(map, rules, next, on_trail);
         }
     }
+    on_trail.remove(&pos);
 
-    let mut sum_ratings = 0;
+    total
+}
+
+/// Cells reachable from a trailhead, in breadth-first order, and for every cell (indexed
+/// `r * cols + c`) whether it is live: reachable from a trailhead and able to reach an end.
+fn trail_cells(map: &[Vec<Height>], rules: &TrailRules) -> (Vec<(usize, usize)>, Vec<bool>) {
+    let cols = map.first().map_or(0, Vec::len);
+    let index = |(r, c): (usize, usize)| r * cols + c;
+
+    // Forward reachability from the trailheads
+    let mut reached = vec![false; map.len() * cols];
+    let mut queue: VecDeque<_> = trailheads(map, rules).collect();
+    for &pos in &queue {
+        reached[index(pos)] = true;
+    }
+    let mut order = Vec::new();
+    while let Some(pos) = queue.pop_front() {
+        order.push(pos);
+        for (next, _) in rules.moves(map, pos) {
+            if !reached[index(next)] {
+                reached[index(next)] = true;
+                queue.push_back(next);
+            }
+        }
+    }
 
-    fn count_paths(
-        r: usize,
-        c: usize,
-        map: &Vec<Vec<u8>>,
-        memo: &mut Vec<Vec<Option<usize>>>,
-    ) -> usize {
-        if map[r][c] == 9 {
-            return 1;
+    // Backward: which of those cells can still reach an end
+    let mut steps_into: Vec<Vec<(usize, usize)>> = vec![Vec::new(); map.len() * cols];
+    for &pos in &order {
+        for (next, _) in rules.moves(map, pos) {
+            steps_into[index(next)].push(pos);
+        }
+    }
+    let mut live = vec![false; map.len() * cols];
+    let mut queue: VecDeque<_> = order
+        .iter()
+        .copied()
+        .filter(|&(r, c)| map[r][c] == Some(rules.end))
+        .collect();
+    for &pos in &queue {
+        live[index(pos)] = true;
+    }
+    while let Some(pos) = queue.pop_front() {
+        for &prev in &steps_into[index(pos)] {
+            if !live[index(prev)] {
+                live[index(prev)] = true;
+                queue.push_back(prev);
+            }
         }
+    }
 
-        if let Some(count) = memo[r][c] {
-            return count;
+    (order, live)
+}
+
+/// Counts trails that may revisit cells, or returns `None` if a loop lies on some trail.
+/// Only cells reachable from a trailhead that can still reach an end take part; on those,
+/// a topological order exists exactly when the number of trails is finite.
+fn count_walks(map: &[Vec<Height>], rules: &TrailRules) -> Option<usize> {
+    let cols = map.first().map_or(0, Vec::len);
+    let index = |(r, c): (usize, usize)| r * cols + c;
+    let (order, live) = trail_cells(map, rules);
+
+    // Kahn's algorithm over the live cells
+    let live_cells: Vec<_> = order.into_iter().filter(|&pos| live[index(pos)]).collect();
+    let mut in_degree = vec![0usize; map.len() * cols];
+    for &pos in &live_cells {
+        for (next, _) in rules.moves(map, pos) {
+            if live[index(next)] {
+                in_degree[index(next)] += 1;
+            }
+        }
+    }
+    let mut ready: Vec<_> = live_cells
+        .iter()
+        .copied()
+        .filter(|&pos| in_degree[index(pos)] == 0)
+        .collect();
+    let mut topo = Vec::new();
+    while let Some(pos) = ready.pop() {
+        topo.push(pos);
+        for (next, _) in rules.moves(map, pos) {
+            if live[index(next)] {
+                in_degree[index(next)] -= 1;
+                if in_degree[index(next)] == 0 {
+                    ready.push(next);
+                }
+            }
         }
+    }
+    if topo.len() < live_cells.len() {
+        return None;
+    }
+
+    // Count trails in reverse topological order
+    let mut trails = vec![0usize; map.len() * cols];
+    for &pos in topo.iter().rev() {
+        trails[index(pos)] = if map[pos.0][pos.1] == Some(rules.end) {
+            1
+        } else {
+            rules
+                .moves(map, pos)
+                .map(|(next, _)| trails[index(next)])
+                .sum()
+        };
+    }
+
+    Some(trailheads(map, rules).map(|pos| trails[index(pos)]).sum())
+}
+
+/// A trail as the cells it visits, from its trailhead to its end.
+pub type Trail = Vec<(usize, usize)>;
 
-        let rows = map.len();
-        let cols = map[0].len();
-        let current_height = map[r][c];
-        let mut total_paths = 0;
+/// Iterator over the distinct trails from one trailhead. Trails that cannot revisit cells
+/// are found depth-first. With revisits there may be endlessly many, so they are found
+/// breadth-first, shortest first, and every trail is reached after finitely many steps.
+/// Only live cells, which can still reach an end, are explored, so the iterator ends
+/// whenever the number of trails is finite.
+pub struct Trails<'a> {
+    map: &'a [Vec<Height>],
+    rules: &'a TrailRules,
+    search: Search,
+}
+
+enum Search {
+    Depth {
+        path: Trail,
+        /// For every cell on `path`, the moves from it that are still to be tried.
+        pending: Vec<Vec<(usize, usize)>>,
+    },
+    Breadth {
+        frontier: VecDeque<Trail>,
+        /// Whether each cell, indexed `r * cols + c`, lies on some trail.
+        live: Vec<bool>,
+    },
+}
+
+impl<'a> Trails<'a> {
+    pub fn new(map: &'a [Vec<Height>], rules: &'a TrailRules, start: (usize, usize)) -> Self {
+        let is_trailhead = map[start.0][start.1] == Some(rules.start);
+        let search = if rules.revisit {
+            let (_, live) = trail_cells(map, rules);
+            let cols = map.first().map_or(0, Vec::len);
+            let frontier = if is_trailhead && live[start.0 * cols + start.1] {
+                vec![vec![start]]
+            } else {
+                vec![]
+            };
+            Search::Breadth {
+                frontier: frontier.into(),
+                live,
+            }
+        } else {
+            Search::Depth {
+                path: Vec::new(),
+                pending: Vec::new(),
+            }
+        };
+        let mut trails = Trails { map, rules, search };
+        if is_trailhead && !rules.revisit {
+            trails.descend(start);
+        }
+        trails
+    }
 
-        let directions = [(-1isize, 0isize), (1, 0), (0, -1), (0, 1)];
-        for &(dr, dc) in &directions {
-            let nr = r as isize + dr;
-            let nc = c as isize + dc;
+    /// Extends the depth-first path by one cell.
+    fn descend(&mut self, pos: (usize, usize)) {
+        let mut moves: Vec<_> = self
+            .rules
+            .moves(self.map, pos)
+            .map(|(next, _)| next)
+            .collect();
+        // Tried from the back, so reverse to explore in neighbourhood order
+        moves.reverse();
+        if let Search::Depth { path, pending } = &mut self.search {
+            path.push(pos);
+            pending.push(moves);
+        }
+    }
+}
 
-            if nr >= 0 && nr < rows as isize && nc >= 0 && nc < cols as isize {
-                let nr_usize = nr as usize;
-                let nc_usize = nc as usize;
+impl Iterator for Trails<'_> {
+    type Item = Trail;
 
-                if map[nr_usize][nc_usize] == current_height + 1 {
-                    total_paths += count_paths(nr_usize, nc_usize, map, memo);
+    fn next(&mut self) -> Option<Trail> {
+        loop {
+            match &mut self.search {
+                Search::Breadth { frontier, live } => {
+                    let trail = frontier.pop_front()?;
+                    let (r, c) = *trail.last()?;
+                    if self.map[r][c] == Some(self.rules.end) {
+                        return Some(trail);
+                    }
+                    let cols = self.map[0].len();
+                    // Loops that never reach an end would keep the frontier from emptying
+                    for (next, _) in self.rules.moves(self.map, (r, c)) {
+                        if !live[next.0 * cols + next.1] {
+                            continue;
+                        }
+                        let mut longer = trail.clone();
+                        longer.push(next);
+                        frontier.push_back(longer);
+                    }
+                }
+                Search::Depth { path, pending } => {
+                    let (r, c) = *path.last()?;
+                    // A trail is complete when its last cell is at the end height, which has
+                    // no moves; report it and backtrack
+                    if self.map[r][c] == Some(self.rules.end) {
+                        let trail = path.clone();
+                        path.pop();
+                        pending.pop();
+                        return Some(trail);
+                    }
+                    match pending.last_mut()?.pop() {
+                        Some(next) if !path.contains(&next) => self.descend(next),
+                        Some(_) => {}
+                        None => {
+                            path.pop();
+                            pending.pop();
+                        }
+                    }
                 }
             }
         }
+    }
+}
 
-        memo[r][c] = Some(total_paths);
-        total_paths
+/// Trails grouped by trailhead, with at most `limit` trails per trailhead if given.
+pub fn trails_by_trailhead(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    limit: Option<usize>,
+) -> BTreeMap<(usize, usize), Vec<Trail>> {
+    trailheads(map, rules)
+        .map(|start| {
+            let trails = Trails::new(map, rules, start).take(limit.unwrap_or(usize::MAX));
+            (start, trails.collect())
+        })
+        .collect()
+}
+
+/// The trail that ranks highest by `key`, among at most `limit` trails per trailhead.
+/// Ties go to the trail found first.
+pub fn best_trail<K: Ord>(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    limit: Option<usize>,
+    mut key: impl FnMut(&[(usize, usize)]) -> K,
+) -> Option<Trail> {
+    let mut best: Option<(K, Trail)> = None;
+    for start in trailheads(map, rules) {
+        for trail in Trails::new(map, rules, start).take(limit.unwrap_or(usize::MAX)) {
+            let k = key(&trail);
+            match &best {
+                Some((best_k, _)) if k <= *best_k => {}
+                _ => best = Some((k, trail)),
+            }
+        }
     }
+    best.map(|(_, trail)| trail)
+}
+
+/// The trail with the most steps.
+pub fn longest_trail(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    limit: Option<usize>,
+) -> Option<Trail> {
+    best_trail(map, rules, limit, <[_]>::len)
+}
+
+/// The most scenic trail: the one that changes direction most often.
+pub fn most_scenic_trail(
+    map: &[Vec<Height>],
+    rules: &TrailRules,
+    limit: Option<usize>,
+) -> Option<Trail> {
+    best_trail(map, rules, limit, turns)
+}
 
-    for &(trail_r, trail_c) in &trailheads {
-        let mut local_memo = vec![vec![None; cols]; rows];
-        let rating = count_paths(trail_r, trail_c, map, &mut local_memo);
-        sum_ratings += rating;
+/// Number of times a trail changes direction.
+pub fn turns(trail: &[(usize, usize)]) -> usize {
+    let steps: Vec<_> = trail
+        .windows(2)
+        .map(|w| {
+            (
+                w[1].0 as isize - w[0].0 as isize,
+                w[1].1 as isize - w[0].1 as isize,
+            )
+        })
+        .collect();
+    steps.windows(2).filter(|w| w[0] != w[1]).count()
+}
+
+/// Exports trails as JSON: a list of `{"trailhead": [r, c], "trails": [[[r, c], ...], ...]}`.
+pub fn trails_to_json(trails: &BTreeMap<(usize, usize), Vec<Trail>>) -> String {
+    let cells = |trail: &[(usize, usize)]| {
+        trail
+            .iter()
+            .map(|(r, c)| format!("[{},{}]", r, c))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let entries: Vec<String> = trails
+        .iter()
+        .map(|(&(r, c), trails)| {
+            let trails: Vec<String> = trails.iter().map(|t| format!("[{}]", cells(t))).collect();
+            format!(
+                "{{\"trailhead\":[{},{}],\"trails\":[{}]}}",
+                r,
+                c,
+                trails.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+/// Draws the map with every cell off the given trails replaced by `.`, like the puzzle's
+/// illustrations.
+pub fn render_trails<'a>(
+    map: &[Vec<Height>],
+    trails: impl IntoIterator<Item = &'a Trail>,
+) -> String {
+    let on_trail: HashSet<(usize, usize)> = trails.into_iter().flatten().copied().collect();
+    map.iter()
+        .enumerate()
+        .map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(c, &height)| {
+                    if on_trail.contains(&(r, c)) {
+                        height
+                            .and_then(|h| char::from_digit(h as u32, 16))
+                            .unwrap_or('?')
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Set of end cells reachable from a cell, stored as offsets inside a square window centred
+/// on the cell, one bit each. With a window radius of `n`, bit `(dr + n) * (2n + 1) + (dc + n)`
+/// stands for the cell at offset (dr, dc). The radius is the longest possible trail, so
+/// every end a cell can reach fits in its window.
+#[derive(Clone)]
+struct Reach(Vec<u64>);
+
+impl Reach {
+    fn empty(radius: usize) -> Self {
+        let side = 2 * radius + 1;
+        Reach(vec![0; (side * side).div_ceil(64)])
+    }
+
+    /// An end cell reaches only itself, the centre of its window.
+    fn end(radius: usize) -> Self {
+        let side = 2 * radius + 1;
+        let centre = side * side / 2;
+        let mut reach = Reach::empty(radius);
+        reach.0[centre / 64] |= 1 << (centre % 64);
+        reach
     }
 
-    sum_ratings
+    /// Adds the reach of a neighbour, moved into this cell's window by shifting it `shift`
+    /// bits. A neighbour one step further along reaches one step less far, so nothing falls
+    /// off the edge of the window.
+    fn union_shifted(&mut self, other: &Reach, shift: isize) {
+        let (words, bits) = (shift.unsigned_abs() / 64, shift.unsigned_abs() % 64);
+        for i in 0..self.0.len() {
+            // Bits of `other` that land in word `i`, from the two source words they straddle
+            let (near, far) = if shift >= 0 {
+                (i.checked_sub(words), i.checked_sub(words + 1))
+            } else {
+                (Some(i + words), Some(i + words + 1))
+            };
+            let near = near.and_then(|k| other.0.get(k)).copied().unwrap_or(0);
+            let far = far.and_then(|k| other.0.get(k)).copied().unwrap_or(0);
+            self.0[i] |= match (shift >= 0, bits) {
+                (_, 0) => near,
+                (true, b) => near << b | far >> (64 - b),
+                (false, b) => near >> b | far << (64 - b),
+            };
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+    use std::collections::{HashSet, VecDeque};
+    use std::time::Instant;
+
+    /// Cells a trail may step to next, checked cell by cell against the rules.
+    fn reference_moves(
+        map: &[Vec<Height>],
+        rules: &TrailRules,
+        (r, c): (usize, usize),
+    ) -> Vec<(usize, usize)> {
+        if map[r][c] == Some(rules.end) {
+            return Vec::new();
+        }
+        let Some(height) = map[r][c] else {
+            return Vec::new();
+        };
+        let mut moves = Vec::new();
+        for nr in r.saturating_sub(1)..=r + 1 {
+            for nc in c.saturating_sub(1)..=c + 1 {
+                let diagonal = nr != r && nc != c;
+                if (nr, nc) == (r, c) || (diagonal && rules.neighbourhood == Neighbourhood::Four) {
+                    continue;
+                }
+                if let Some(&Some(next)) = map.get(nr).and_then(|row| row.get(nc)) {
+                    if rules.step.contains(&(next as i16 - height as i16)) {
+                        moves.push((nr, nc));
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    fn reference_trailheads(map: &[Vec<Height>], rules: &TrailRules) -> Vec<(usize, usize)> {
+        let mut heads = Vec::new();
+        for (r, row) in map.iter().enumerate() {
+            for (c, &height) in row.iter().enumerate() {
+                if height == Some(rules.start) {
+                    heads.push((r, c));
+                }
+            }
+        }
+        heads
+    }
+
+    /// Reference score: a separate BFS from every trailhead.
+    fn reference_scores(map: &[Vec<Height>], rules: &TrailRules) -> usize {
+        reference_trailheads(map, rules)
+            .into_iter()
+            .map(|start| {
+                let mut visited = HashSet::from([start]);
+                let mut queue = VecDeque::from([start]);
+                let mut ends = 0;
+                while let Some(pos) = queue.pop_front() {
+                    if map[pos.0][pos.1] == Some(rules.end) {
+                        ends += 1;
+                    }
+                    for next in reference_moves(map, rules, pos) {
+                        if visited.insert(next) {
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                ends
+            })
+            .sum()
+    }
+
+    /// Reference rating: enumerates every trail that does not revisit a cell.
+    fn reference_ratings(map: &[Vec<Height>], rules: &TrailRules) -> usize {
+        fn count_paths(
+            map: &[Vec<Height>],
+            rules: &TrailRules,
+            path: &mut Vec<(usize, usize)>,
+        ) -> usize {
+            let pos = *path.last().unwrap();
+            if map[pos.0][pos.1] == Some(rules.end) {
+                return 1;
+            }
+            let mut total = 0;
+            for next in reference_moves(map, rules, pos) {
+                if !path.contains(&next) {
+                    path.push(next);
+                    total += count_paths(map, rules, path);
+                    path.pop();
+                }
+            }
+            total
+        }
+        reference_trailheads(map, rules)
+            .into_iter()
+            .map(|start| count_paths(map, rules, &mut vec![start]))
+            .sum()
+    }
+
+    /// Rolling terrain: diagonal slopes with pseudo-random bumps, so trails branch and merge.
+    fn generate_map(size: usize, seed: u64) -> Vec<Vec<Height>> {
+        let mut state = seed;
+        let mut next = move || {
+            // 64-bit LCG (Knuth's MMIX constants)
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 33
+        };
+        (0..size)
+            .map(|r| {
+                (0..size)
+                    .map(|c| {
+                        let bump = if next() % 4 == 0 { next() % 10 } else { 0 };
+                        Some(((r + c) as u64 + bump) as u8 % 10)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    const EXAMPLE: &str = indoc! {
+        "89010123
+         78121874
+         87430965
+         96549874
+         45678903
+         32019012
+         01329801
+         10456732"
+    };
 
     #[test]
     fn example_part1() {
-        let input = indoc! {
-            "89010123
-             78121874
-             87430965
-             96549874
-             45678903
-             32019012
-             01329801
-             10456732"
-        };
-        let map = parse(input);
+        let map = parse(EXAMPLE).unwrap();
         assert_eq!(day10_part1(&map), 36);
     }
 
     #[test]
     fn example_part2() {
-        let input = indoc! {
-            "89010123
-             78121874
-             87430965
-             96549874
-             45678903
-             32019012
-             01329801
-             10456732"
-        };
-        let map = parse(input);
+        let map = parse(EXAMPLE).unwrap();
         assert_eq!(day10_part2(&map), 81);
     }
+
+    #[test]
+    fn matches_reference_on_generated_maps() {
+        for seed in 0..5 {
+            let map = generate_map(60, seed);
+            let rules = TrailRules::default();
+            assert_eq!(
+                day10_part1(&map),
+                reference_scores(&map, &rules),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                day10_part2(&map),
+                reference_ratings(&map, &rules),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn empty_map() {
+        assert_eq!(day10_part1(&[]), 0);
+        assert_eq!(day10_part2(&[]), 0);
+    }
+
+    /// Timing comparison on a large map; run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_large_generated_map() {
+        let map = generate_map(1000, 2024);
+
+        let start = Instant::now();
+        let (score, rating) = (day10_part1(&map), day10_part2(&map));
+        let layered = start.elapsed();
+
+        let start = Instant::now();
+        let rules = TrailRules::default();
+        let (ref_score, ref_rating) = (
+            reference_scores(&map, &rules),
+            reference_ratings(&map, &rules),
+        );
+        let per_trailhead = start.elapsed();
+
+        assert_eq!((score, rating), (ref_score, ref_rating));
+        println!(
+            "1000x1000: layered DP {:?}, per-trailhead search {:?}",
+            layered, per_trailhead
+        );
+    }
+
+    #[test]
+    fn descending_trails() {
+        // Walking every trail backwards connects the same trailhead/summit pairs
+        let map = parse(EXAMPLE).unwrap();
+        let rules = TrailRules {
+            step: -1..=-1,
+            start: 9,
+            end: 0,
+            ..TrailRules::default()
+        };
+        assert_eq!(trail_scores(&map, &rules), 36);
+        assert_eq!(trail_ratings(&map, &rules), Some(81));
+    }
+
+    #[test]
+    fn diagonal_moves() {
+        let map = parse(indoc! {
+            "012
+             123
+             234"
+        })
+        .unwrap();
+        let mut rules = TrailRules {
+            end: 4,
+            ..TrailRules::default()
+        };
+        // Only the six monotone lattice paths climb by exactly one
+        assert_eq!(trail_scores(&map, &rules), 1);
+        assert_eq!(trail_ratings(&map, &rules), Some(6));
+
+        // Diagonals climb by two, so they only help with steeper steps allowed
+        rules.neighbourhood = Neighbourhood::Eight;
+        assert_eq!(trail_ratings(&map, &rules), Some(6));
+        rules.step = 1..=2;
+        assert_eq!(trail_scores(&map, &rules), 1);
+        assert_eq!(trail_ratings(&map, &rules), Some(13));
+    }
+
+    #[test]
+    fn level_steps_and_revisits() {
+        let map = parse("0023456789").unwrap();
+        let mut rules = TrailRules {
+            step: 0..=2,
+            ..TrailRules::default()
+        };
+        // Each 0 has a direct trail, and the left one can also cross the flat first
+        assert_eq!(trail_scores(&map, &rules), 2);
+        assert_eq!(trail_ratings(&map, &rules), Some(2));
+
+        // Stepping back and forth between the two 0s never ends
+        rules.revisit = true;
+        assert_eq!(trail_scores(&map, &rules), 2);
+        assert_eq!(trail_ratings(&map, &rules), None);
+
+        // A loop that cannot lead to the end does not make the count infinite
+        let map = parse("22023456789").unwrap();
+        assert_eq!(trail_ratings(&map, &rules), Some(1));
+    }
+
+    #[test]
+    fn generated_maps_under_varied_rules() {
+        let rule_sets = [
+            TrailRules {
+                neighbourhood: Neighbourhood::Eight,
+                ..TrailRules::default()
+            },
+            TrailRules {
+                step: 1..=3,
+                ..TrailRules::default()
+            },
+            TrailRules {
+                step: -2..=-1,
+                start: 9,
+                end: 0,
+                neighbourhood: Neighbourhood::Eight,
+                ..TrailRules::default()
+            },
+            TrailRules {
+                step: 0..=1,
+                start: 2,
+                end: 6,
+                ..TrailRules::default()
+            },
+            TrailRules {
+                step: -1..=1,
+                start: 3,
+                end: 5,
+                ..TrailRules::default()
+            },
+        ];
+        for seed in 0..3 {
+            let map = generate_map(10, seed);
+            for rules in &rule_sets {
+                assert_eq!(
+                    trail_scores(&map, rules),
+                    reference_scores(&map, rules),
+                    "seed {}, {:?}",
+                    seed,
+                    rules
+                );
+                assert_eq!(
+                    trail_ratings(&map, rules),
+                    Some(reference_ratings(&map, rules)),
+                    "seed {}, {:?}",
+                    seed,
+                    rules
+                );
+                let enumerated: usize = trails_by_trailhead(&map, rules, None)
+                    .values()
+                    .map(Vec::len)
+                    .sum();
+                assert_eq!(enumerated, reference_ratings(&map, rules));
+            }
+        }
+    }
+
+    #[test]
+    fn enumerate_example_trails() {
+        let map = parse(EXAMPLE).unwrap();
+        let rules = TrailRules::default();
+        let trails = trails_by_trailhead(&map, &rules, None);
+
+        // 9 trailheads whose ratings add up to 81, every trail distinct and valid
+        assert_eq!(trails.len(), 9);
+        let all: Vec<&Trail> = trails.values().flatten().collect();
+        assert_eq!(all.len(), 81);
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 81);
+        for trail in &all {
+            assert_eq!(trail.len(), 10);
+            for (i, &(r, c)) in trail.iter().enumerate() {
+                assert_eq!(map[r][c], Some(i as u8));
+            }
+            for w in trail.windows(2) {
+                assert_eq!(w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1), 1);
+            }
+        }
+
+        // The ratings listed in the puzzle, in reading order of the trailheads
+        let ratings: Vec<usize> = trails.values().map(Vec::len).collect();
+        assert_eq!(ratings, vec![20, 24, 10, 4, 1, 4, 5, 8, 5]);
+
+        let limited = trails_by_trailhead(&map, &rules, Some(3));
+        assert!(limited.values().all(|trails| trails.len() <= 3));
+        assert_eq!(limited[&(0, 2)], trails[&(0, 2)][..3].to_vec());
+    }
+
+    #[test]
+    fn render_and_export_trails() {
+        let map = parse(indoc! {
+            "0123
+             1234
+             8765
+             9876"
+        })
+        .unwrap();
+        let rules = TrailRules::default();
+        let trails = trails_by_trailhead(&map, &rules, None);
+        let trails_from_corner = &trails[&(0, 0)];
+        assert_eq!(trails_from_corner.len(), 16);
+
+        let first = &trails_from_corner[0];
+        assert_eq!(
+            render_trails(&map, [first]),
+            indoc! {
+                "0...
+                 1234
+                 ...5
+                 9876"
+            }
+        );
+        assert_eq!(
+            render_trails(&map, trails_from_corner),
+            "0123\n1234\n8765\n9876"
+        );
+
+        let single = parse("0123456789").unwrap();
+        let json = trails_to_json(&trails_by_trailhead(&single, &rules, None));
+        assert_eq!(
+            json,
+            "[{\"trailhead\":[0,0],\"trails\":[[[0,0],[0,1],[0,2],[0,3],[0,4],[0,5],[0,6],[0,7],[0,8],[0,9]]]}]"
+        );
+    }
+
+    #[test]
+    fn trail_queries() {
+        let map = parse(EXAMPLE).unwrap();
+        let rules = TrailRules::default();
+        // Every puzzle trail has nine steps, so the first one found is the longest
+        let longest = longest_trail(&map, &rules, None).unwrap();
+        assert_eq!(longest.len(), 10);
+        assert_eq!(longest, Trails::new(&map, &rules, (0, 2)).next().unwrap());
+
+        let scenic = most_scenic_trail(&map, &rules, None).unwrap();
+        let max_turns = trails_by_trailhead(&map, &rules, None)
+            .values()
+            .flatten()
+            .map(|t| turns(t))
+            .max();
+        assert_eq!(Some(turns(&scenic)), max_turns);
+
+        assert_eq!(turns(&[(0, 0), (0, 1), (0, 2)]), 0);
+        assert_eq!(turns(&[(0, 0), (0, 1), (1, 1), (1, 2)]), 2);
+    }
+
+    #[test]
+    fn trails_with_revisits_can_be_limited() {
+        // Stepping back and forth between the two 0s gives endless distinct trails
+        let map = parse("0023456789").unwrap();
+        let rules = TrailRules {
+            step: 0..=2,
+            revisit: true,
+            ..TrailRules::default()
+        };
+        let trails: Vec<Trail> = Trails::new(&map, &rules, (0, 0)).take(5).collect();
+        assert_eq!(trails.len(), 5);
+        assert_eq!(trails.iter().collect::<HashSet<_>>().len(), 5);
+    }
+
+    #[test]
+    fn trails_beside_dead_end_loops_run_out() {
+        let rules = TrailRules {
+            step: 0..=0,
+            revisit: true,
+            ..TrailRules::default()
+        };
+        let map = parse("00").unwrap();
+        assert_eq!(Trails::new(&map, &rules, (0, 0)).next(), None);
+        assert_eq!(longest_trail(&map, &rules, Some(5)), None);
+
+        // The 1s on the right can step back and forth forever without reaching the 2
+        let map = parse(indoc! {
+            "210
+             ..1
+             ..1"
+        })
+        .unwrap();
+        let rules = TrailRules {
+            step: 0..=1,
+            end: 2,
+            revisit: true,
+            ..TrailRules::default()
+        };
+        let trails: Vec<Trail> = Trails::new(&map, &rules, (0, 2)).take(5).collect();
+        assert_eq!(trails, [vec![(0, 2), (0, 1), (0, 0)]]);
+        assert_eq!(trail_ratings(&map, &rules), Some(1));
+    }
+
+    #[test]
+    fn impassable_examples_part1() {
+        let map = parse(indoc! {
+            "...0...
+             ...1...
+             ...2...
+             6543456
+             7.....7
+             8.....8
+             9.....9"
+        })
+        .unwrap();
+        assert_eq!(day10_part1(&map), 2);
+
+        let map = parse(indoc! {
+            "..90..9
+             ...1.98
+             ...2..7
+             6543456
+             765.987
+             876....
+             987...."
+        })
+        .unwrap();
+        assert_eq!(day10_part1(&map), 4);
+        assert_eq!(day10_part2(&map), 13);
+
+        // Two trailheads: the top one scores 1, the bottom one 2
+        let map = parse(indoc! {
+            "10..9..
+             2...8..
+             3...7..
+             4567654
+             ...8..3
+             ...9..2
+             .....01"
+        })
+        .unwrap();
+        assert_eq!(day10_part1(&map), 3);
+    }
+
+    #[test]
+    fn impassable_examples_part2() {
+        let map = parse(indoc! {
+            ".....0.
+             ..4321.
+             ..5..2.
+             ..6543.
+             ..7..4.
+             ..8765.
+             ..9...."
+        })
+        .unwrap();
+        assert_eq!(day10_part2(&map), 3);
+
+        let map = parse(indoc! {
+            "012345
+             123456
+             234567
+             345678
+             4.6789
+             56789."
+        })
+        .unwrap();
+        assert_eq!(day10_part2(&map), 227);
+    }
+
+    #[test]
+    fn impassable_cells_match_reference() {
+        // Punch holes into generated terrain
+        for seed in 0..3 {
+            let mut map = generate_map(20, seed);
+            for (r, row) in map.iter_mut().enumerate() {
+                for (c, cell) in row.iter_mut().enumerate() {
+                    if (r * 7 + c * 3 + seed as usize) % 5 == 2 {
+                        *cell = None;
+                    }
+                }
+            }
+            let rules = TrailRules::default();
+            assert_eq!(day10_part1(&map), reference_scores(&map, &rules));
+            assert_eq!(day10_part2(&map), reference_ratings(&map, &rules));
+        }
+    }
+
+    #[test]
+    fn long_trails_are_searched_per_trailhead() {
+        // A climb from 0 to 200 would need a window of 401x401 bits per cell
+        let row: Vec<String> = (0..=200).map(|h| h.to_string()).collect();
+        let map = parse(&format!("{}\n{}", row.join(" "), row.join(" "))).unwrap();
+        let rules = TrailRules {
+            end: 200,
+            ..TrailRules::default()
+        };
+        assert!(max_trail_length(&rules) > MAX_LAYERED_TRAIL);
+        assert_eq!(trail_scores(&map, &rules), 2);
+        assert_eq!(trail_scores(&map, &rules), reference_scores(&map, &rules));
+    }
+
+    #[test]
+    fn heights_beyond_nine() {
+        let rules = TrailRules {
+            end: 15,
+            ..TrailRules::default()
+        };
+        let map = parse(indoc! {
+            "0123456789abcdef
+             123456789abcdef."
+        })
+        .unwrap();
+        assert_eq!(map[0][15], Some(15));
+        assert_eq!(map[1][15], None);
+        assert_eq!(trail_scores(&map, &rules), 2);
+        assert_eq!(
+            trail_ratings(&map, &rules),
+            Some(reference_ratings(&map, &rules))
+        );
+
+        // Separated decimal tokens allow any height up to 255
+        let map = parse(indoc! {
+            "10 11 12
+             .  .  13
+             16 15 14"
+        })
+        .unwrap();
+        assert_eq!(map[2][0], Some(16));
+        assert_eq!(map[1][1], None);
+        let rules = TrailRules {
+            start: 10,
+            end: 16,
+            ..TrailRules::default()
+        };
+        assert_eq!(trail_ratings(&map, &rules), Some(1));
+        assert_eq!(
+            parse("0,1,2").unwrap(),
+            vec![vec![Some(0), Some(1), Some(2)]]
+        );
+    }
+
+    #[test]
+    fn invalid_heights() {
+        assert_eq!(
+            parse("0123\n01x3").unwrap_err(),
+            "invalid height \"x\" at row 2, column 3"
+        );
+        assert_eq!(
+            parse("1 2 300").unwrap_err(),
+            "invalid height \"300\" at row 1, column 3"
+        );
+    }
+
+    #[test]
+    fn ragged_map_is_rejected() {
+        assert_eq!(
+            parse("01\n12345678\n9").unwrap_err(),
+            "ragged grid: row 2 has 8 columns, expected 2"
+        );
+    }
+
+    #[test]
+    fn mixed_formats_are_not_combined() {
+        // The unseparated row is one decimal height, not three hex digits
+        assert_eq!(
+            parse("0 1 2\n123").unwrap_err(),
+            "ragged grid: row 2 has 1 columns, expected 3"
+        );
+        assert_eq!(
+            parse("0,1\nab").unwrap_err(),
+            "invalid height \"ab\" at row 2, column 1"
+        );
+    }
+
+    fn height_map() -> impl Strategy<Value = Vec<Vec<Height>>> {
+        (1usize..8, 1usize..8).prop_flat_map(|(rows, cols)| {
+            let cell = prop_oneof![1 => Just(None), 6 => (0u8..10).prop_map(Some)];
+            prop::collection::vec(prop::collection::vec(cell, cols), rows)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn random_maps_match_reference(map in height_map()) {
+            let rules = TrailRules::default();
+            prop_assert_eq!(day10_part1(&map), reference_scores(&map, &rules));
+            prop_assert_eq!(day10_part2(&map), reference_ratings(&map, &rules));
+        }
+    }
 }
@@ -0,0 +1,121 @@
+use crate::solution::Solution;
+use std::collections::HashMap;
+
+/// How many stones carry each number. Stones never affect their neighbours, so their order
+/// doesn't matter and equal stones can evolve together.
+pub type Stones = HashMap<u64, u64>;
+
+#[aoc_generator(day11)]
+pub fn parse(input: &str) -> Result<Stones, String> {
+    let mut stones = Stones::new();
+    for token in input.split_whitespace() {
+        let value = token
+            .parse::<u64>()
+            .map_err(|_| format!("invalid stone {:?}", token))?;
+        *stones.entry(value).or_insert(0) += 1;
+    }
+    Ok(stones)
+}
+
+#[aoc(day11, part1)]
+fn day11_part1(stones: &Stones) -> u64 {
+    count(&blink_times(stones, 25))
+}
+
+#[aoc(day11, part2)]
+fn day11_part2(stones: &Stones) -> u64 {
+    count(&blink_times(stones, 75))
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input = Stones;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        day11_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        day11_part2(input)
+    }
+}
+
+/// Total number of stones.
+pub fn count(stones: &Stones) -> u64 {
+    stones.values().sum()
+}
+
+/// What a single stone turns into after one blink.
+pub fn evolve(value: u64) -> (u64, Option<u64>) {
+    if value == 0 {
+        return (1, None);
+    }
+    let digits = value.ilog10() + 1;
+    if digits & 1 == 0 {
+        let half = 10u64.pow(digits / 2);
+        (value / half, Some(value % half))
+    } else {
+        (value * 2024, None)
+    }
+}
+
+/// Applies the rules to every stone once.
+pub fn blink(stones: &Stones) -> Stones {
+    let mut next = Stones::with_capacity(stones.len());
+    for (&value, &n) in stones {
+        let (left, right) = evolve(value);
+        *next.entry(left).or_insert(0) += n;
+        if let Some(right) = right {
+            *next.entry(right).or_insert(0) += n;
+        }
+    }
+    next
+}
+
+/// Blinks `times` times.
+pub fn blink_times(stones: &Stones, times: usize) -> Stones {
+    (0..times).fold(stones.clone(), |stones, _| blink(&stones))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part1() {
+        let input = "125 17";
+        let stones = parse(input).unwrap();
+        assert_eq!(count(&blink_times(&stones, 6)), 22);
+        assert_eq!(day11_part1(&stones), 55312);
+    }
+
+    #[test]
+    fn single_blink() {
+        let stones = parse("0 1 10 99 999").unwrap();
+        assert_eq!(blink(&stones), parse("1 2024 1 0 9 9 2021976").unwrap());
+        assert_eq!(
+            blink_times(&parse("125 17").unwrap(), 6),
+            parse("2097446912 14168 4048 2 0 2 4 40 48 2024 40 48 80 96 2 8 6 7 6 0 3 2").unwrap()
+        );
+    }
+
+    #[test]
+    fn example_part2() {
+        let stones = parse("125 17").unwrap();
+        assert_eq!(day11_part2(&stones), 65601038650482);
+    }
+
+    #[test]
+    fn invalid_stones() {
+        assert_eq!(parse("12 x3").unwrap_err(), "invalid stone \"x3\"");
+    }
+}
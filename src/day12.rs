@@ -0,0 +1,251 @@
+use crate::grid::{offset_in, ORTHOGONAL};
+use crate::solution::Solution;
+
+#[aoc_generator(day12)]
+fn parse(input: &str) -> Result<Vec<Vec<char>>, String> {
+    crate::grid::parse_chars(input)
+}
+
+#[aoc(day12, part1)]
+fn day12_part1(garden: &[Vec<char>]) -> usize {
+    Regions::label(garden)
+        .regions
+        .iter()
+        .map(|region| region.area * region.perimeter)
+        .sum()
+}
+
+#[aoc(day12, part2)]
+fn day12_part2(garden: &[Vec<char>]) -> usize {
+    Regions::label(garden)
+        .regions
+        .iter()
+        .map(|region| region.area * region.sides)
+        .sum()
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    type Input = Vec<Vec<char>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day12_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day12_part2(input)
+    }
+}
+
+/// A set of orthogonally connected cells holding equal values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// The region's first cell in reading order.
+    pub start: (usize, usize),
+    pub area: usize,
+    /// Number of cell edges bordering another region or the outside.
+    pub perimeter: usize,
+    /// Number of straight fence sections, counted as the number of corners.
+    pub sides: usize,
+}
+
+/// Every region of a grid, with each cell labelled by the index of its region.
+#[derive(Clone, Debug)]
+pub struct Regions {
+    pub labels: Vec<Vec<usize>>,
+    /// Regions in the reading order of their first cells.
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Flood-fills the grid into regions of equal neighbouring values.
+    pub fn label<T: PartialEq>(grid: &[Vec<T>]) -> Self {
+        let mut labels: Vec<Vec<usize>> =
+            grid.iter().map(|row| vec![usize::MAX; row.len()]).collect();
+        let mut starts = Vec::new();
+
+        for r in 0..grid.len() {
+            for c in 0..grid[r].len() {
+                if labels[r][c] != usize::MAX {
+                    continue;
+                }
+                let label = starts.len();
+                starts.push((r, c));
+                labels[r][c] = label;
+                let mut stack = vec![(r, c)];
+                while let Some(pos) = stack.pop() {
+                    // Rows may differ in width, so every step is checked against its own row
+                    for next in ORTHOGONAL.iter().filter_map(|&d| offset_in(grid, pos, d)) {
+                        if labels[next.0][next.1] == usize::MAX
+                            && grid[next.0][next.1] == grid[r][c]
+                        {
+                            labels[next.0][next.1] = label;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        let regions = starts
+            .into_iter()
+            .map(|start| Region {
+                start,
+                area: 0,
+                perimeter: 0,
+                sides: 0,
+            })
+            .collect();
+        let mut out = Regions { labels, regions };
+
+        for r in 0..out.labels.len() {
+            for c in 0..out.labels[r].len() {
+                let label = out.labels[r][c];
+                let same = |d| out.label_at((r, c), d) == Some(label);
+                let perimeter = ORTHOGONAL.iter().filter(|&&d| !same(d)).count();
+                // Each corner of the fence starts a new side: convex where both neighbours
+                // are outside the region, concave where only the diagonal one is
+                let sides = (0..4)
+                    .filter(|&i| {
+                        let (a, b) = (ORTHOGONAL[i], ORTHOGONAL[(i + 1) & 3]);
+                        match (same(a), same(b)) {
+                            (false, false) => true,
+                            (true, true) => !same((a.0 + b.0, a.1 + b.1)),
+                            _ => false,
+                        }
+                    })
+                    .count();
+
+                let region = &mut out.regions[label];
+                region.area += 1;
+                region.perimeter += perimeter;
+                region.sides += sides;
+            }
+        }
+
+        out
+    }
+
+    /// The region containing a cell.
+    pub fn region_at(&self, (r, c): (usize, usize)) -> &Region {
+        &self.regions[self.labels[r][c]]
+    }
+
+    /// Label of the cell offset by `d` from `pos`, if it is on the grid.
+    fn label_at(&self, pos: (usize, usize), d: (isize, isize)) -> Option<usize> {
+        offset_in(&self.labels, pos, d).map(|(r, c)| self.labels[r][c])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const SMALL: &str = indoc! {"
+        AAAA
+        BBCD
+        BBCC
+        EEEC
+    "};
+
+    const ENCLOSED: &str = indoc! {"
+        OOOOO
+        OXOXO
+        OOOOO
+        OXOXO
+        OOOOO
+    "};
+
+    const LARGE: &str = indoc! {"
+        RRRRIICCFF
+        RRRRIICCCF
+        VVRRRCCFFF
+        VVRCCCJFFF
+        VVVVCJJCFE
+        VVIVCCJJEE
+        VVIIICJJEE
+        MIIIIIJJEE
+        MIIISIJEEE
+        MMMISSJEEE
+    "};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(day12_part1(&parse(SMALL).unwrap()), 140);
+        assert_eq!(day12_part1(&parse(ENCLOSED).unwrap()), 772);
+        assert_eq!(day12_part1(&parse(LARGE).unwrap()), 1930);
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(day12_part2(&parse(SMALL).unwrap()), 80);
+        assert_eq!(day12_part2(&parse(ENCLOSED).unwrap()), 436);
+        assert_eq!(day12_part2(&parse(LARGE).unwrap()), 1206);
+
+        let e_shape = indoc! {"
+            EEEEE
+            EXXXX
+            EEEEE
+            EXXXX
+            EEEEE
+        "};
+        assert_eq!(day12_part2(&parse(e_shape).unwrap()), 236);
+
+        let touching_corners = indoc! {"
+            AAAAAA
+            AAABBA
+            AAABBA
+            ABBAAA
+            ABBAAA
+            AAAAAA
+        "};
+        assert_eq!(day12_part2(&parse(touching_corners).unwrap()), 368);
+    }
+
+    #[test]
+    fn labelled_regions() {
+        let regions = Regions::label(&parse(SMALL).unwrap());
+        assert_eq!(regions.regions.len(), 5);
+        assert_eq!(
+            regions.region_at((2, 3)),
+            &Region {
+                start: (1, 2),
+                area: 4,
+                perimeter: 10,
+                sides: 8,
+            }
+        );
+        assert_eq!(regions.labels[3], vec![4, 4, 4, 2]);
+
+        // Equal values that don't touch are separate regions
+        let regions = Regions::label(&parse(ENCLOSED).unwrap());
+        assert_eq!(regions.regions.len(), 5);
+        assert_eq!(regions.regions[0].area, 21);
+        assert_eq!(regions.regions[0].sides, 20);
+
+        let regions = Regions::label(&[vec![1, 1, 2], vec![3, 1, 2]]);
+        assert_eq!(regions.labels, vec![vec![0, 0, 1], vec![2, 0, 1]]);
+
+        // Rows of different widths are fenced where they end
+        let regions = Regions::label(&[vec![1, 1, 1], vec![1]]);
+        assert_eq!(
+            regions.regions,
+            [Region {
+                start: (0, 0),
+                area: 4,
+                perimeter: 10,
+                sides: 6,
+            }]
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use crate::solution::Solution;
+
+/// Tokens it costs to press each button.
+pub const COST_A: i64 = 3;
+pub const COST_B: i64 = 1;
+
+/// Most times part 1 lets each button be pressed.
+pub const PRESS_LIMIT: i64 = 100;
+
+/// How far part 2 actually moves every prize along both axes.
+pub const PRIZE_OFFSET: i64 = 10_000_000_000_000;
+
+/// A claw machine: how far each button moves the claw, and where the prize is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Machine {
+    pub a: (i64, i64),
+    pub b: (i64, i64),
+    pub prize: (i64, i64),
+}
+
+#[aoc_generator(day13)]
+pub fn parse(input: &str) -> Result<Vec<Machine>, String> {
+    input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .enumerate()
+        .map(|(i, block)| {
+            let lines: Vec<&str> = block.lines().map(str::trim).collect();
+            let [a, b, prize] = lines[..] else {
+                return Err(format!("machine {} does not have 3 lines", i + 1));
+            };
+            Ok(Machine {
+                a: parse_pair(a, "Button A:", '+')?,
+                b: parse_pair(b, "Button B:", '+')?,
+                prize: parse_pair(prize, "Prize:", '=')?,
+            })
+        })
+        .collect()
+}
+
+/// Parses e.g. `Prize: X=8400, Y=5400`. Negative moves are written `X-5`.
+fn parse_pair(line: &str, prefix: &str, sep: char) -> Result<(i64, i64), String> {
+    let invalid = || format!("invalid line {:?}", line);
+    let (x, y) = line
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.split_once(','))
+        .ok_or_else(invalid)?;
+    let coordinate = |text: &str, axis: char| {
+        let text = text.trim().strip_prefix(axis)?;
+        text.strip_prefix(sep).unwrap_or(text).parse::<i64>().ok()
+    };
+    Ok((
+        coordinate(x, 'X').ok_or_else(invalid)?,
+        coordinate(y, 'Y').ok_or_else(invalid)?,
+    ))
+}
+
+#[aoc(day13, part1)]
+fn day13_part1(machines: &[Machine]) -> i64 {
+    machines
+        .iter()
+        .filter_map(|machine| machine.cost(Some(PRESS_LIMIT)))
+        .sum()
+}
+
+#[aoc(day13, part2)]
+fn day13_part2(machines: &[Machine]) -> i64 {
+    machines
+        .iter()
+        .filter_map(|machine| machine.offset(PRIZE_OFFSET).cost(None))
+        .sum()
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input = Vec<Machine>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> i64 {
+        day13_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> i64 {
+        day13_part2(input)
+    }
+}
+
+/// Tokens needed for the given numbers of A and B presses.
+pub fn cost((a, b): (i64, i64)) -> i64 {
+    a * COST_A + b * COST_B
+}
+
+impl Machine {
+    /// The same machine with the prize moved by `by` along both axes.
+    pub fn offset(&self, by: i64) -> Machine {
+        Machine {
+            prize: (self.prize.0 + by, self.prize.1 + by),
+            ..*self
+        }
+    }
+
+    /// The cheapest numbers of A and B presses that reach the prize, pressing neither button
+    /// more than `limit` times if there is a limit, if the prize can be won.
+    pub fn presses(&self, limit: Option<i64>) -> Option<(i64, i64)> {
+        let (ax, ay) = (self.a.0 as i128, self.a.1 as i128);
+        let (bx, by) = (self.b.0 as i128, self.b.1 as i128);
+        let (px, py) = (self.prize.0 as i128, self.prize.1 as i128);
+
+        let det = ax * by - ay * bx;
+        if det == 0 {
+            return self.presses_collinear(limit);
+        }
+
+        // Cramer's rule; the only solution must be whole and non-negative
+        let a = px * by - py * bx;
+        let b = ax * py - ay * px;
+        if a % det != 0 || b % det != 0 {
+            return None;
+        }
+        let (a, b) = (a / det, b / det);
+        let limit = limit.map_or(i128::MAX, i128::from);
+        if a < 0 || b < 0 || a > limit || b > limit {
+            return None;
+        }
+        Some((i64::try_from(a).ok()?, i64::try_from(b).ok()?))
+    }
+
+    /// Tokens needed to win the prize within the press limit, if it can be won.
+    pub fn cost(&self, limit: Option<i64>) -> Option<i64> {
+        self.presses(limit).map(cost)
+    }
+
+    /// Solves a machine whose buttons move the claw along the same line, where there may be
+    /// many ways to reach the prize.
+    fn presses_collinear(&self, limit: Option<i64>) -> Option<(i64, i64)> {
+        let cross =
+            |u: (i64, i64), v: (i64, i64)| u.0 as i128 * v.1 as i128 - u.1 as i128 * v.0 as i128;
+        // Measure along whichever axis the line isn't perpendicular to
+        let Some(dir) = [self.a, self.b].into_iter().find(|&v| v != (0, 0)) else {
+            return (self.prize == (0, 0)).then_some((0, 0));
+        };
+        if cross(dir, self.prize) != 0 {
+            return None;
+        }
+        let along = |v: (i64, i64)| (if dir.0 != 0 { v.0 } else { v.1 }) as i128;
+        let (a, b) = min_cost_presses(
+            along(self.a),
+            along(self.b),
+            along(self.prize),
+            limit.map(i128::from),
+        )?;
+        Some((i64::try_from(a).ok()?, i64::try_from(b).ok()?))
+    }
+}
+
+/// Cheapest non-negative `(x, y)` with `a * x + b * y == target`, both at most `limit` if
+/// there is one.
+fn min_cost_presses(a: i128, b: i128, target: i128, limit: Option<i128>) -> Option<(i128, i128)> {
+    let limit = limit.unwrap_or(i128::MAX);
+    // A button that doesn't move the claw is never worth pressing
+    let only = |step: i128| {
+        let n = if step == 0 { 0 } else { target / step };
+        (n * step == target && (0..=limit).contains(&n)).then_some(n)
+    };
+    match (a, b) {
+        (0, _) => return only(b).map(|y| (0, y)),
+        (_, 0) => return only(a).map(|x| (x, 0)),
+        _ => {}
+    }
+
+    let (g, x, y) = extended_gcd(a, b);
+    if target % g != 0 {
+        return None;
+    }
+    // All solutions are (x0 + k * sx, y0 - k * sy)
+    let (x0, y0) = (x * (target / g), y * (target / g));
+    let (sx, sy) = (b / g, a / g);
+
+    let mut lo = None;
+    let mut hi = None;
+    // Narrows k to where v0 + k * step >= min
+    let mut at_least = |v0: i128, step: i128, min: i128| {
+        if step > 0 {
+            let k = div_ceil(min - v0, step);
+            lo = Some(lo.map_or(k, |lo: i128| lo.max(k)));
+        } else {
+            let k = div_floor(min - v0, step);
+            hi = Some(hi.map_or(k, |hi: i128| hi.min(k)));
+        }
+    };
+    // 0 <= x0 + k * sx <= limit and 0 <= y0 - k * sy <= limit
+    at_least(x0, sx, 0);
+    at_least(y0, -sy, 0);
+    if limit < i128::MAX {
+        at_least(-x0, -sx, -limit);
+        at_least(-y0, sy, -limit);
+    }
+    if let (Some(lo), Some(hi)) = (lo, hi) {
+        if lo > hi {
+            return None;
+        }
+    }
+
+    // The cost is linear in k, and never negative, so it is lowest at one of the bounds
+    let slope = COST_A as i128 * sx - COST_B as i128 * sy;
+    let k = if slope > 0 { lo } else { hi }.or(lo).or(hi)?;
+    Some((x0 + k * sx, y0 - k * sy))
+}
+
+/// `(g, x, y)` with `a * x + b * y == g`, where `g` is the positive gcd of `a` and `b`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a.abs(), a.signum(), 0);
+    }
+    let (g, x, y) = extended_gcd(b, a % b);
+    (g, y, x - (a / b) * y)
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -div_floor(-a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        Button A: X+94, Y+34
+        Button B: X+22, Y+67
+        Prize: X=8400, Y=5400
+
+        Button A: X+26, Y+66
+        Button B: X+67, Y+21
+        Prize: X=12748, Y=12176
+
+        Button A: X+17, Y+86
+        Button B: X+84, Y+37
+        Prize: X=7870, Y=6450
+
+        Button A: X+69, Y+23
+        Button B: X+27, Y+71
+        Prize: X=18641, Y=10279
+    "};
+
+    fn machine(a: (i64, i64), b: (i64, i64), prize: (i64, i64)) -> Machine {
+        Machine { a, b, prize }
+    }
+
+    #[test]
+    fn example_part1() {
+        let machines = parse(EXAMPLE).unwrap();
+        assert_eq!(machines[0].presses(Some(PRESS_LIMIT)), Some((80, 40)));
+        assert_eq!(machines[1].presses(Some(PRESS_LIMIT)), None);
+        assert_eq!(machines[2].presses(Some(PRESS_LIMIT)), Some((38, 86)));
+        assert_eq!(machines[2].presses(Some(85)), None);
+        assert_eq!(machines[3].presses(Some(PRESS_LIMIT)), None);
+        assert_eq!(day13_part1(&machines), 480);
+    }
+
+    #[test]
+    fn example_part2() {
+        let machines = parse(EXAMPLE).unwrap();
+        let costs: Vec<_> = machines
+            .iter()
+            .map(|m| m.offset(PRIZE_OFFSET).cost(None).is_some())
+            .collect();
+        assert_eq!(costs, [false, true, false, true]);
+        assert_eq!(day13_part2(&machines), 875318608908);
+    }
+
+    #[test]
+    fn collinear_buttons() {
+        // Three B presses beat nine A presses or a mix of both
+        assert_eq!(machine((1, 1), (3, 3), (9, 9)).presses(None), Some((0, 3)));
+        // A moves six times as far for three times the cost
+        assert_eq!(machine((6, 6), (1, 1), (7, 7)).presses(None), Some((1, 1)));
+        assert_eq!(
+            machine((6, 6), (1, 1), (12, 12)).presses(None),
+            Some((2, 0))
+        );
+        assert_eq!(machine((0, 2), (0, 3), (0, 7)).presses(None), Some((2, 1)));
+        assert_eq!(machine((2, 2), (4, 4), (7, 7)).presses(None), None);
+        assert_eq!(machine((1, 1), (2, 2), (3, 4)).presses(None), None);
+        assert_eq!(machine((1, 1), (2, 2), (-2, -2)).presses(None), None);
+        assert_eq!(machine((0, 0), (2, 1), (4, 2)).presses(None), Some((0, 2)));
+        assert_eq!(machine((0, 0), (0, 0), (0, 0)).presses(None), Some((0, 0)));
+        assert_eq!(machine((0, 0), (0, 0), (1, 0)).presses(None), None);
+        // Buttons pulling in opposite directions
+        assert_eq!(
+            machine((5, 5), (-3, -3), (1, 1)).presses(None),
+            Some((2, 3))
+        );
+
+        // The cheapest way may press a button too often when a dearer one doesn't
+        let m = machine((2, 2), (1, 1), (300, 300));
+        assert_eq!(m.presses(None), Some((0, 300)));
+        assert_eq!(m.presses(Some(PRESS_LIMIT)), Some((100, 100)));
+        assert_eq!(m.presses(Some(99)), None);
+        assert_eq!(
+            machine((1, 1), (3, 3), (9, 9)).presses(Some(3)),
+            Some((0, 3))
+        );
+        assert_eq!(machine((1, 1), (3, 3), (9, 9)).presses(Some(2)), None);
+        assert_eq!(machine((0, 0), (2, 1), (4, 2)).presses(Some(1)), None);
+        assert_eq!(machine((5, 5), (-3, -3), (1, 1)).presses(Some(2)), None);
+        assert_eq!(
+            machine((5, 5), (-3, -3), (1, 1)).presses(Some(100)),
+            Some((2, 3))
+        );
+        assert_eq!(day13_part1(&[m]), 400);
+    }
+
+    #[test]
+    fn invalid_machines() {
+        assert_eq!(
+            parse("Button A: X+1, Y+2\nButton B: X+3, Y+4").unwrap_err(),
+            "machine 1 does not have 3 lines"
+        );
+        assert_eq!(
+            parse("Button A: X+1, Y+2\nButton B: X+3, Z+4\nPrize: X=1, Y=2").unwrap_err(),
+            "invalid line \"Button B: X+3, Z+4\""
+        );
+        assert_eq!(
+            parse("Button A: X-1, Y+2\nButton B: X+3, Y+4\nPrize: X=1, Y=2").unwrap()[0].a,
+            (-1, 2)
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use crate::solution::Solution;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Robot {
+    pub pos: (i64, i64),
+    pub vel: (i64, i64),
+}
+
+/// The area robots move in. They wrap around its edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Space {
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Space {
+    /// The space of the puzzle examples.
+    pub const EXAMPLE: Space = Space {
+        width: 11,
+        height: 7,
+    };
+    /// The space of the real puzzle inputs.
+    pub const REAL: Space = Space {
+        width: 101,
+        height: 103,
+    };
+}
+
+#[aoc_generator(day14)]
+pub fn parse(input: &str) -> Result<Vec<Robot>, String> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let invalid = || format!("invalid robot {:?}", line);
+            let pair = |text: &str, prefix: &str| {
+                let (x, y) = text.strip_prefix(prefix)?.split_once(',')?;
+                Some((x.parse().ok()?, y.parse().ok()?))
+            };
+            let (p, v) = line.trim().split_once(' ').ok_or_else(invalid)?;
+            Ok(Robot {
+                pos: pair(p, "p=").ok_or_else(invalid)?,
+                vel: pair(v, "v=").ok_or_else(invalid)?,
+            })
+        })
+        .collect()
+}
+
+#[aoc(day14, part1)]
+fn day14_part1(robots: &[Robot]) -> usize {
+    Space::REAL.safety_factor(&Space::REAL.positions_after(robots, 100))
+}
+
+#[aoc(day14, part2)]
+fn day14_part2(robots: &[Robot]) -> i64 {
+    Space::REAL
+        .tree_second(robots)
+        .expect("no Christmas tree found")
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input = Vec<Robot>;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day14_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> i64 {
+        day14_part2(input)
+    }
+}
+
+impl Space {
+    /// Where every robot is after `seconds` seconds.
+    pub fn positions_after(&self, robots: &[Robot], seconds: i64) -> Vec<(i64, i64)> {
+        robots
+            .iter()
+            .map(|robot| {
+                (
+                    (robot.pos.0 + robot.vel.0 * seconds).rem_euclid(self.width),
+                    (robot.pos.1 + robot.vel.1 * seconds).rem_euclid(self.height),
+                )
+            })
+            .collect()
+    }
+
+    /// Product of the number of robots in each quadrant. Robots on the middle lines don't count.
+    pub fn safety_factor(&self, positions: &[(i64, i64)]) -> usize {
+        let (mid_x, mid_y) = (self.width / 2, self.height / 2);
+        let mut quadrants = [0; 4];
+        for &(x, y) in positions {
+            if x == mid_x || y == mid_y {
+                continue;
+            }
+            quadrants[(x > mid_x) as usize + 2 * (y > mid_y) as usize] += 1;
+        }
+        quadrants.iter().product()
+    }
+
+    /// The first second at which the robots arrange themselves into a picture.
+    ///
+    /// Each axis repeats with its own period, and the picture is where robots bunch up the
+    /// most along both: the second with the least variance per axis, combined with the
+    /// Chinese remainder theorem. If the periods share a factor the seconds are searched
+    /// instead for the first frame where no two robots overlap.
+    pub fn tree_second(&self, robots: &[Robot]) -> Option<i64> {
+        if robots.is_empty() {
+            return None;
+        }
+        if gcd(self.width, self.height) != 1 {
+            let period = self.width / gcd(self.width, self.height) * self.height;
+            return (0..period).find(|&t| no_overlap(&self.positions_after(robots, t)));
+        }
+
+        let tightest = |period: i64, axis: fn(&(i64, i64)) -> i64| {
+            (0..period).min_by_key(|&t| {
+                let values: Vec<i64> = self.positions_after(robots, t).iter().map(axis).collect();
+                variance_times_n2(&values)
+            })
+        };
+        let tx = tightest(self.width, |p| p.0)?;
+        let ty = tightest(self.height, |p| p.1)?;
+
+        // t = tx + width * k with t = ty (mod height)
+        let inverse = mod_inverse(self.width, self.height)?;
+        let k = ((ty - tx) * inverse).rem_euclid(self.height);
+        Some(tx + self.width * k)
+    }
+
+    /// Draws the robots, showing how many share each tile.
+    pub fn render(&self, positions: &[(i64, i64)]) -> String {
+        let mut counts = vec![vec![0u32; self.width as usize]; self.height as usize];
+        for &(x, y) in positions {
+            counts[y as usize][x as usize] += 1;
+        }
+        counts
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&n| match n {
+                        0 => '.',
+                        n => char::from_digit(n.min(9), 10).unwrap(),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// `n² · variance`, which orders sets of the same size like the variance does.
+fn variance_times_n2(values: &[i64]) -> i64 {
+    let n = values.len() as i64;
+    let sum: i64 = values.iter().sum();
+    let squares: i64 = values.iter().map(|v| v * v).sum();
+    n * squares - sum * sum
+}
+
+fn no_overlap(positions: &[(i64, i64)]) -> bool {
+    let mut seen = HashSet::new();
+    positions.iter().all(|&p| seen.insert(p))
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// `x` with `a * x = 1 (mod m)`.
+fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    (0..m).find(|&x| (a * x).rem_euclid(m) == 1 % m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        p=0,4 v=3,-3
+        p=6,3 v=-1,-3
+        p=10,3 v=-1,2
+        p=2,0 v=2,-1
+        p=0,0 v=1,3
+        p=3,0 v=-2,-2
+        p=7,6 v=-1,-3
+        p=3,0 v=-1,-2
+        p=9,3 v=2,3
+        p=7,3 v=-1,2
+        p=2,4 v=2,-3
+        p=9,5 v=-3,-3
+    "};
+
+    #[test]
+    fn example_part1() {
+        let robots = parse(EXAMPLE).unwrap();
+        let positions = Space::EXAMPLE.positions_after(&robots, 100);
+        assert_eq!(
+            Space::EXAMPLE.render(&positions),
+            indoc! {"
+                ......2..1.
+                ...........
+                1..........
+                .11........
+                .....1.....
+                ...12......
+                .1....1...."}
+        );
+        assert_eq!(Space::EXAMPLE.safety_factor(&positions), 12);
+    }
+
+    #[test]
+    fn single_robot() {
+        let robot = parse("p=2,4 v=2,-3").unwrap();
+        let path: Vec<_> = (0..=5)
+            .map(|t| Space::EXAMPLE.positions_after(&robot, t)[0])
+            .collect();
+        assert_eq!(path, [(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)]);
+    }
+
+    /// Robots that draw a filled triangle at `second`, among as many scattered ones.
+    fn hidden_tree(space: Space, second: i64, seed: u64) -> Vec<Robot> {
+        let rng = &mut Rng::new(seed);
+        let mut picture = Vec::new();
+        for row in 0..15 {
+            for col in -row..=row {
+                picture.push((40 + col, 30 + row));
+            }
+        }
+        let scattered: Vec<(i64, i64)> = (0..picture.len())
+            .map(|_| {
+                (
+                    rng.range(0, space.width as usize) as i64,
+                    rng.range(0, space.height as usize) as i64,
+                )
+            })
+            .collect();
+        picture
+            .into_iter()
+            .map(|(x, y)| (x, y, true))
+            .chain(scattered.into_iter().map(|(x, y)| (x, y, false)))
+            .map(|(x, y, in_picture)| {
+                let vel = (
+                    rng.range(1, 200) as i64 - 100,
+                    rng.range(1, 200) as i64 - 100,
+                );
+                // Scattered robots are somewhere else at every second
+                let at = if in_picture {
+                    second
+                } else {
+                    rng.range(0, 10_000) as i64
+                };
+                Robot {
+                    pos: (
+                        (x - vel.0 * at).rem_euclid(space.width),
+                        (y - vel.1 * at).rem_euclid(space.height),
+                    ),
+                    vel,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_tree() {
+        for (second, seed) in [(7037, 1), (1, 2), (10402, 3)] {
+            let robots = hidden_tree(Space::REAL, second, seed);
+            assert_eq!(Space::REAL.tree_second(&robots), Some(second));
+            let frame = Space::REAL.render(&Space::REAL.positions_after(&robots, second));
+            let base = frame.lines().nth(44).unwrap();
+            assert!(!base[26..=54].contains('.'));
+        }
+        assert_eq!(Space::REAL.tree_second(&[]), None);
+    }
+
+    #[test]
+    fn finds_tree_without_overlaps() {
+        // 12 and 8 share a factor, so the axes can't be combined
+        let space = Space {
+            width: 12,
+            height: 8,
+        };
+        let robots = [
+            Robot {
+                pos: (0, 0),
+                vel: (1, 0),
+            },
+            Robot {
+                pos: (0, 0),
+                vel: (3, 0),
+            },
+        ];
+        assert_eq!(space.tree_second(&robots), Some(1));
+    }
+
+    #[test]
+    fn invalid_robots() {
+        assert_eq!(
+            parse("p=0,4 v=3,-3\np=6 v=-1,-3").unwrap_err(),
+            "invalid robot \"p=6 v=-1,-3\""
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use crate::day6::{dir_from_arrow, dir_to_delta, Dir};
+use crate::solution::Solution;
+
+/// The warehouse map, with the robot kept apart from the tiles it stands on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warehouse {
+    /// `#` walls, `.` floor, and boxes as `O` or, once widened, `[]`.
+    pub grid: Vec<Vec<char>>,
+    pub robot: (usize, usize),
+}
+
+pub type Input = (Warehouse, Vec<Dir>);
+
+#[aoc_generator(day15)]
+pub fn parse(input: &str) -> Result<Input, String> {
+    let (map, moves) = input
+        .split_once("\n\n")
+        .ok_or("expected a map and moves separated by a blank line")?;
+
+    let mut grid: Vec<Vec<char>> = map.lines().map(|line| line.chars().collect()).collect();
+    let mut robot = None;
+    for (r, row) in grid.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            match *cell {
+                '@' if robot.is_some() => return Err("more than one robot".to_string()),
+                '@' => {
+                    robot = Some((r, c));
+                    *cell = '.';
+                }
+                '#' | '.' | 'O' | '[' | ']' => {}
+                other => {
+                    return Err(format!(
+                        "invalid tile {:?} at row {}, column {}",
+                        other,
+                        r + 1,
+                        c + 1
+                    ))
+                }
+            }
+        }
+    }
+    let robot = robot.ok_or("no robot on the map")?;
+
+    let moves = moves
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| dir_from_arrow(c).ok_or_else(|| format!("invalid move {:?}", c)))
+        .collect::<Result<_, _>>()?;
+
+    Ok((Warehouse { grid, robot }, moves))
+}
+
+#[aoc(day15, part1)]
+fn day15_part1((warehouse, moves): &Input) -> usize {
+    let mut warehouse = warehouse.clone();
+    warehouse.run(moves);
+    warehouse.gps_sum()
+}
+
+#[aoc(day15, part2)]
+fn day15_part2((warehouse, moves): &Input) -> usize {
+    let mut warehouse = warehouse.widen();
+    warehouse.run(moves);
+    warehouse.gps_sum()
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day15_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day15_part2(input)
+    }
+}
+
+impl Warehouse {
+    /// The same warehouse with everything except the robot twice as wide.
+    pub fn widen(&self) -> Warehouse {
+        let grid = self
+            .grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .flat_map(|&cell| match cell {
+                        'O' => ['[', ']'],
+                        cell => [cell, cell],
+                    })
+                    .collect()
+            })
+            .collect();
+        Warehouse {
+            grid,
+            robot: (self.robot.0, self.robot.1 * 2),
+        }
+    }
+
+    /// Tries to move the robot one step, pushing any boxes in the way. Returns whether it moved.
+    pub fn step(&mut self, dir: Dir) -> bool {
+        let (dr, dc) = dir_to_delta(dir);
+        let next = |(r, c): (usize, usize)| (r.wrapping_add_signed(dr), c.wrapping_add_signed(dc));
+
+        // Everything that moves: the robot, then each box touched by something moving
+        let mut moving = vec![self.robot];
+        let mut i = 0;
+        while i < moving.len() {
+            let (r, c) = next(moving[i]);
+            i += 1;
+            let touched = match self.grid.get(r).and_then(|row| row.get(c)) {
+                Some('.') => continue,
+                Some('O') => [Some((r, c)), None],
+                Some('[') => [Some((r, c)), Some((r, c + 1))],
+                Some(']') => [Some((r, c)), Some((r, c - 1))],
+                _ => return false,
+            };
+            for cell in touched.into_iter().flatten() {
+                if !moving.contains(&cell) {
+                    moving.push(cell);
+                }
+            }
+        }
+
+        let boxes: Vec<((usize, usize), char)> = moving[1..]
+            .iter()
+            .map(|&(r, c)| ((r, c), self.grid[r][c]))
+            .collect();
+        for &((r, c), _) in &boxes {
+            self.grid[r][c] = '.';
+        }
+        for &(pos, tile) in &boxes {
+            let (r, c) = next(pos);
+            self.grid[r][c] = tile;
+        }
+        self.robot = next(self.robot);
+        true
+    }
+
+    /// Makes every move in order.
+    pub fn run(&mut self, moves: &[Dir]) {
+        self.run_with(moves, |_, _| {});
+    }
+
+    /// Makes every move in order, calling `frame` after each one with the move's index, e.g.
+    /// to print [`Warehouse::render`] while debugging.
+    pub fn run_with(&mut self, moves: &[Dir], mut frame: impl FnMut(usize, &Warehouse)) {
+        for (i, &dir) in moves.iter().enumerate() {
+            self.step(dir);
+            frame(i, self);
+        }
+    }
+
+    /// Sum of `100 * row + column` over the boxes, measured at their left edge.
+    pub fn gps_sum(&self) -> usize {
+        self.grid
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &cell)| cell == 'O' || cell == '[')
+                    .map(move |(c, _)| 100 * r + c)
+            })
+            .sum()
+    }
+
+    /// Draws the warehouse like the puzzle does, with the robot as `@`.
+    pub fn render(&self) -> String {
+        self.grid
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(c, &cell)| if (r, c) == self.robot { '@' } else { cell })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const SMALL: &str = indoc! {"
+        ########
+        #..O.O.#
+        ##@.O..#
+        #...O..#
+        #.#.O..#
+        #...O..#
+        #......#
+        ########
+
+        <^^>>>vv<v>>v<<
+    "};
+
+    const LARGE: &str = indoc! {"
+        ##########
+        #..O..O.O#
+        #......O.#
+        #.OO..O.O#
+        #..O@..O.#
+        #O#..O...#
+        #O..O..O.#
+        #.OO.O.OO#
+        #....O...#
+        ##########
+
+        <vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+        vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+        ><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+        <<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+        ^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+        ^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+        >^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+        <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+        ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+        v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
+    "};
+
+    #[test]
+    fn example_part1() {
+        let (mut warehouse, moves) = parse(SMALL).unwrap();
+        warehouse.run(&moves);
+        assert_eq!(
+            warehouse.render(),
+            indoc! {"
+                ########
+                #....OO#
+                ##.....#
+                #.....O#
+                #.#O@..#
+                #...O..#
+                #...O..#
+                ########"}
+        );
+        assert_eq!(warehouse.gps_sum(), 2028);
+        assert_eq!(day15_part1(&parse(LARGE).unwrap()), 10092);
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = indoc! {"
+            #######
+            #...#.#
+            #.....#
+            #..OO@#
+            #..O..#
+            #.....#
+            #######
+
+            <vv<<^^<<^^
+        "};
+        let (warehouse, moves) = parse(input).unwrap();
+        let mut warehouse = warehouse.widen();
+        let mut frames = vec![warehouse.render()];
+        warehouse.run_with(&moves, |_, warehouse| frames.push(warehouse.render()));
+        assert_eq!(
+            frames[0],
+            indoc! {"
+                ##############
+                ##......##..##
+                ##..........##
+                ##....[][]@.##
+                ##....[]....##
+                ##..........##
+                ##############"}
+        );
+        // Pushing up moves both boxes resting under the pushed one
+        assert_eq!(
+            frames[7],
+            indoc! {"
+                ##############
+                ##......##..##
+                ##...[][]...##
+                ##....[]....##
+                ##.....@....##
+                ##..........##
+                ##############"}
+        );
+        assert_eq!(
+            frames[11],
+            indoc! {"
+                ##############
+                ##...[].##..##
+                ##...@.[]...##
+                ##....[]....##
+                ##..........##
+                ##..........##
+                ##############"}
+        );
+        assert_eq!(warehouse.gps_sum(), 105 + 207 + 306);
+        assert_eq!(day15_part2(&parse(LARGE).unwrap()), 9021);
+    }
+
+    #[test]
+    fn blocked_pushes() {
+        let (mut warehouse, _) = parse("#####\n#.@O#\n#####\n\n>").unwrap();
+        assert!(!warehouse.step(Dir::Right));
+        assert!(warehouse.step(Dir::Left));
+        assert_eq!(warehouse.render(), "#####\n#@.O#\n#####");
+
+        let wide = indoc! {"
+            ##########
+            ##......##
+            ##..[]..##
+            ##.[][].##
+            ##..@...##
+            ##########
+
+            ^
+        "};
+        let (mut warehouse, _) = parse(wide).unwrap();
+        assert!(warehouse.step(Dir::Up));
+        assert_eq!(
+            warehouse.render(),
+            indoc! {"
+                ##########
+                ##..[]..##
+                ##.[]...##
+                ##..@[].##
+                ##......##
+                ##########"}
+        );
+
+        // One box of the group is against a wall, so none of them move
+        let (mut warehouse, _) = parse(&wide.replacen("##......##", "##...#..##", 1)).unwrap();
+        let before = warehouse.render();
+        assert!(!warehouse.step(Dir::Up));
+        assert_eq!(warehouse.render(), before);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            parse("#@#").unwrap_err(),
+            "expected a map and moves separated by a blank line"
+        );
+        assert_eq!(parse("#.#\n\n<").unwrap_err(), "no robot on the map");
+        assert_eq!(
+            parse("#@x\n\n<").unwrap_err(),
+            "invalid tile 'x' at row 1, column 3"
+        );
+        assert_eq!(parse("#@.\n\n<x").unwrap_err(), "invalid move 'x'");
+    }
+}
@@ -0,0 +1,184 @@
+use crate::day6::{dir_to_delta, turn_left, turn_right, Dir};
+use crate::path::{dijkstra, ShortestPaths};
+use crate::solution::Solution;
+use std::collections::HashSet;
+
+/// Cost of moving one tile forward.
+pub const MOVE_COST: u64 = 1;
+/// Cost of turning 90 degrees on the spot.
+pub const TURN_COST: u64 = 1000;
+
+/// Where the reindeer is and which way it faces.
+pub type State = ((usize, usize), Dir);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Maze {
+    pub grid: Vec<Vec<char>>,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+#[aoc_generator(day16)]
+pub fn parse(input: &str) -> Result<Maze, String> {
+    let grid = crate::grid::parse_chars(input)?;
+    let find = |tile: char| {
+        grid.iter()
+            .enumerate()
+            .find_map(|(r, row)| Some((r, row.iter().position(|&cell| cell == tile)?)))
+            .ok_or_else(|| format!("no {} tile in the maze", tile))
+    };
+    let (start, end) = (find('S')?, find('E')?);
+    Ok(Maze { grid, start, end })
+}
+
+#[aoc(day16, part1)]
+fn day16_part1(maze: &Maze) -> u64 {
+    let paths = maze.shortest_paths();
+    paths
+        .best(&maze.end_states())
+        .expect("the end is unreachable")
+        .1
+}
+
+#[aoc(day16, part2)]
+fn day16_part2(maze: &Maze) -> usize {
+    maze.best_tiles().len()
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    type Input = Maze;
+    type Answer1 = u64;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        day16_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day16_part2(input)
+    }
+}
+
+impl Maze {
+    /// States one move or turn away, with their costs.
+    pub fn neighbours(&self, &(pos, dir): &State) -> Vec<(State, u64)> {
+        let mut next = vec![
+            ((pos, turn_left(dir)), TURN_COST),
+            ((pos, turn_right(dir)), TURN_COST),
+        ];
+        let (dr, dc) = dir_to_delta(dir);
+        let ahead = (pos.0.wrapping_add_signed(dr), pos.1.wrapping_add_signed(dc));
+        if matches!(self.grid.get(ahead.0).and_then(|row| row.get(ahead.1)), Some(&tile) if tile != '#')
+        {
+            next.push(((ahead, dir), MOVE_COST));
+        }
+        next
+    }
+
+    /// Cheapest paths from the start, where the reindeer faces east.
+    pub fn shortest_paths(&self) -> ShortestPaths<State> {
+        dijkstra([(self.start, Dir::Right)], |state| self.neighbours(state))
+    }
+
+    /// The end tile, facing every way.
+    pub fn end_states(&self) -> [State; 4] {
+        [Dir::Up, Dir::Right, Dir::Down, Dir::Left].map(|dir| (self.end, dir))
+    }
+
+    /// Every tile on at least one of the best paths.
+    pub fn best_tiles(&self) -> HashSet<(usize, usize)> {
+        self.shortest_paths()
+            .on_best_paths(&self.end_states())
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const FIRST: &str = indoc! {"
+        ###############
+        #.......#....E#
+        #.#.###.#.###.#
+        #.....#.#...#.#
+        #.###.#####.#.#
+        #.#.#.......#.#
+        #.#.#####.###.#
+        #...........#.#
+        ###.#.#####.#.#
+        #...#.....#.#.#
+        #.#.#.###.#.#.#
+        #.....#...#.#.#
+        #.###.#.#.#.#.#
+        #S..#.....#...#
+        ###############
+    "};
+
+    const SECOND: &str = indoc! {"
+        #################
+        #...#...#...#..E#
+        #.#.#.#.#.#.#.#.#
+        #.#.#.#...#...#.#
+        #.#.#.#.###.#.#.#
+        #...#.#.#.....#.#
+        #.#.#.#.#.#####.#
+        #.#...#.#.#.....#
+        #.#.#####.#.###.#
+        #.#.#.......#...#
+        #.#.###.#####.###
+        #.#.#...#.....#.#
+        #.#.#.#####.###.#
+        #.#.#.........#.#
+        #.#.#.#########.#
+        #S#.............#
+        #################
+    "};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(day16_part1(&parse(FIRST).unwrap()), 7036);
+        assert_eq!(day16_part1(&parse(SECOND).unwrap()), 11048);
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(day16_part2(&parse(FIRST).unwrap()), 45);
+        assert_eq!(day16_part2(&parse(SECOND).unwrap()), 64);
+    }
+
+    #[test]
+    fn best_path() {
+        let maze = parse(FIRST).unwrap();
+        let paths = maze.shortest_paths();
+        let ends = maze.end_states();
+        let (end, cost) = paths.best(&ends).unwrap();
+        let path = paths.path(end).unwrap();
+        assert_eq!(path.first(), Some(&(maze.start, Dir::Right)));
+        assert_eq!(path.last(), Some(end));
+        // 36 moves and 7 turns
+        assert_eq!(cost, 36 * MOVE_COST + 7 * TURN_COST);
+        let turns = path.windows(2).filter(|w| w[0].1 != w[1].1).count();
+        assert_eq!(turns, 7);
+    }
+
+    #[test]
+    fn invalid_mazes() {
+        assert_eq!(parse("#S.#").unwrap_err(), "no E tile in the maze");
+        assert_eq!(
+            parse("#S.#\n#E#").unwrap_err(),
+            "ragged grid: row 2 has 3 columns, expected 4"
+        );
+    }
+}
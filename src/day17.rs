@@ -0,0 +1,471 @@
+use crate::solution::Solution;
+use std::fmt;
+
+/// Initial registers and the program of 3-bit numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Program {
+    /// Registers A, B and C.
+    pub registers: [u64; 3],
+    pub code: Vec<u8>,
+}
+
+#[aoc_generator(day17)]
+pub fn parse(input: &str) -> Result<Program, String> {
+    let mut registers = [0; 3];
+    let mut code = None;
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(program) = line.strip_prefix("Program:") {
+            code = Some(
+                program
+                    .split(',')
+                    .map(|n| match n.trim().parse::<u8>() {
+                        Ok(n) if n < 8 => Ok(n),
+                        _ => Err(format!("invalid 3-bit number {:?}", n.trim())),
+                    })
+                    .collect::<Result<Vec<u8>, String>>()?,
+            );
+            continue;
+        }
+        let (name, value) = line
+            .strip_prefix("Register ")
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(|| format!("invalid line {:?}", line))?;
+        let index = ["A", "B", "C"]
+            .iter()
+            .position(|&r| r == name)
+            .ok_or_else(|| format!("unknown register {:?}", name))?;
+        registers[index] = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid value for register {}: {:?}", name, value.trim()))?;
+    }
+    Ok(Program {
+        registers,
+        code: code.ok_or("no program")?,
+    })
+}
+
+#[aoc(day17, part1)]
+fn day17_part1(program: &Program) -> String {
+    let output = Vm::new(program).run().expect("program failed");
+    join(&output)
+}
+
+#[aoc(day17, part2)]
+fn day17_part2(program: &Program) -> u64 {
+    find_quine(program).expect("no value of A makes the program output itself")
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+
+    type Input = Program;
+    type Answer1 = String;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> String {
+        day17_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        day17_part2(input)
+    }
+}
+
+/// Formats output the way the puzzle expects it, e.g. `4,6,3`.
+pub fn join(output: &[u8]) -> String {
+    output
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opcode {
+    /// A = A >> combo
+    Adv,
+    /// B = B ^ literal
+    Bxl,
+    /// B = combo & 7
+    Bst,
+    /// Jump to literal if A is not zero
+    Jnz,
+    /// B = B ^ C, ignoring the operand
+    Bxc,
+    /// Output combo & 7
+    Out,
+    /// B = A >> combo
+    Bdv,
+    /// C = A >> combo
+    Cdv,
+}
+
+impl Opcode {
+    pub fn from_u8(n: u8) -> Opcode {
+        match n & 7 {
+            0 => Opcode::Adv,
+            1 => Opcode::Bxl,
+            2 => Opcode::Bst,
+            3 => Opcode::Jnz,
+            4 => Opcode::Bxc,
+            5 => Opcode::Out,
+            6 => Opcode::Bdv,
+            _ => Opcode::Cdv,
+        }
+    }
+
+    /// Whether the operand is a combo operand rather than a literal one.
+    pub fn takes_combo(self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+/// One instruction, displayed the way [`disassemble`] prints it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: u8,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let combo = match self.operand {
+            n @ 0..=3 => n.to_string(),
+            4 => "A".to_string(),
+            5 => "B".to_string(),
+            6 => "C".to_string(),
+            _ => "?".to_string(),
+        };
+        match self.opcode {
+            Opcode::Adv => write!(f, "adv  A = A >> {}", combo),
+            Opcode::Bxl => write!(f, "bxl  B = B ^ {}", self.operand),
+            Opcode::Bst => write!(f, "bst  B = {} & 7", combo),
+            Opcode::Jnz => write!(f, "jnz  if A != 0 goto {}", self.operand),
+            Opcode::Bxc => write!(f, "bxc  B = B ^ C"),
+            Opcode::Out => write!(f, "out  output {} & 7", combo),
+            Opcode::Bdv => write!(f, "bdv  B = A >> {}", combo),
+            Opcode::Cdv => write!(f, "cdv  C = A >> {}", combo),
+        }
+    }
+}
+
+/// Lists a program's instructions with their addresses, one per line.
+pub fn disassemble(code: &[u8]) -> String {
+    code.chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let instruction = Instruction {
+                opcode: Opcode::from_u8(pair[0]),
+                operand: pair.get(1).copied().unwrap_or(0),
+            };
+            format!("{:3}: {}", 2 * i, instruction)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The 3-bit computer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vm {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+    /// Address of the next instruction.
+    pub ip: usize,
+    pub output: Vec<u8>,
+    pub code: Vec<u8>,
+}
+
+impl Vm {
+    pub fn new(program: &Program) -> Self {
+        let [a, b, c] = program.registers;
+        Vm {
+            a,
+            b,
+            c,
+            ip: 0,
+            output: Vec::new(),
+            code: program.code.clone(),
+        }
+    }
+
+    /// The instruction at the instruction pointer, or `None` once the program has halted.
+    pub fn current(&self) -> Option<Instruction> {
+        Some(Instruction {
+            opcode: Opcode::from_u8(*self.code.get(self.ip)?),
+            operand: *self.code.get(self.ip + 1)?,
+        })
+    }
+
+    fn combo(&self, operand: u8) -> Result<u64, String> {
+        match operand {
+            0..=3 => Ok(operand as u64),
+            4 => Ok(self.a),
+            5 => Ok(self.b),
+            6 => Ok(self.c),
+            _ => Err(format!("reserved combo operand {} at {}", operand, self.ip)),
+        }
+    }
+
+    /// Executes one instruction. Returns `false` if the program had already halted.
+    pub fn step(&mut self) -> Result<bool, String> {
+        let Some(Instruction { opcode, operand }) = self.current() else {
+            return Ok(false);
+        };
+        let value = if opcode.takes_combo() {
+            self.combo(operand)?
+        } else {
+            operand as u64
+        };
+        // Shifting out every bit leaves zero
+        let shifted = || self.a.checked_shr(value.min(64) as u32).unwrap_or(0);
+
+        self.ip += 2;
+        match opcode {
+            Opcode::Adv => self.a = shifted(),
+            Opcode::Bxl => self.b ^= value,
+            Opcode::Bst => self.b = value & 7,
+            Opcode::Jnz => {
+                if self.a != 0 {
+                    self.ip = value as usize;
+                }
+            }
+            Opcode::Bxc => self.b ^= self.c,
+            Opcode::Out => self.output.push((value & 7) as u8),
+            Opcode::Bdv => self.b = shifted(),
+            Opcode::Cdv => self.c = shifted(),
+        }
+        Ok(true)
+    }
+
+    /// Runs until the program halts and returns its output.
+    pub fn run(&mut self) -> Result<Vec<u8>, String> {
+        while self.step()? {}
+        Ok(self.output.clone())
+    }
+
+    /// Runs until the program halts, describing every instruction executed and the registers
+    /// after it.
+    pub fn trace(&mut self) -> Result<Vec<String>, String> {
+        let mut lines = Vec::new();
+        while let Some(instruction) = self.current() {
+            let ip = self.ip;
+            self.step()?;
+            lines.push(format!(
+                "{:3}: {:<24} A={} B={} C={}",
+                ip,
+                instruction.to_string(),
+                self.a,
+                self.b,
+                self.c
+            ));
+        }
+        Ok(lines)
+    }
+}
+
+/// Smallest initial A for which the program outputs its own code.
+///
+/// Programs like the puzzle's loop once per output, dropping the lowest three bits of A each
+/// time, so the last output depends only on A's highest octal digit, the one before on the
+/// two highest, and so on. The digits are found from the highest down, keeping every choice
+/// that reproduces the end of the program.
+pub fn find_quine(program: &Program) -> Option<u64> {
+    let code = &program.code;
+    let output_for = |a: u64| {
+        let mut vm = Vm::new(program);
+        vm.a = a;
+        // Give up on programs that run away
+        for _ in 0..100_000 {
+            if !vm.step().ok()? || vm.output.len() > code.len() {
+                break;
+            }
+        }
+        Some(vm.output)
+    };
+
+    let mut candidates = vec![0u64];
+    for i in (0..code.len()).rev() {
+        candidates = candidates
+            .iter()
+            .flat_map(|&high| (0..8).map(move |digit| high << 3 | digit))
+            .filter(|&a| output_for(a).is_some_and(|output| output == code[i..]))
+            .collect();
+    }
+    candidates.into_iter().min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn vm(registers: [u64; 3], code: &[u8]) -> Vm {
+        Vm::new(&Program {
+            registers,
+            code: code.to_vec(),
+        })
+    }
+
+    #[test]
+    fn example_part1() {
+        let input = indoc! {"
+            Register A: 729
+            Register B: 0
+            Register C: 0
+
+            Program: 0,1,5,4,3,0
+        "};
+        assert_eq!(day17_part1(&parse(input).unwrap()), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = indoc! {"
+            Register A: 2024
+            Register B: 0
+            Register C: 0
+
+            Program: 0,3,5,4,3,0
+        "};
+        let program = parse(input).unwrap();
+        assert_eq!(day17_part2(&program), 117440);
+        let mut vm = Vm::new(&program);
+        vm.a = 117440;
+        assert_eq!(vm.run().unwrap(), program.code);
+    }
+
+    #[test]
+    fn small_examples() {
+        let mut m = vm([0, 0, 9], &[2, 6]);
+        m.run().unwrap();
+        assert_eq!(m.b, 1);
+
+        assert_eq!(
+            vm([10, 0, 0], &[5, 0, 5, 1, 5, 4]).run().unwrap(),
+            [0, 1, 2]
+        );
+
+        let mut m = vm([2024, 0, 0], &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(m.run().unwrap(), [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(m.a, 0);
+
+        let mut m = vm([0, 29, 0], &[1, 7]);
+        m.run().unwrap();
+        assert_eq!(m.b, 26);
+
+        let mut m = vm([0, 2024, 43690], &[4, 0]);
+        m.run().unwrap();
+        assert_eq!(m.b, 44354);
+    }
+
+    #[test]
+    fn adv() {
+        let mut m = vm([100, 2, 0], &[0, 5]);
+        m.step().unwrap();
+        assert_eq!((m.a, m.ip), (25, 2));
+        // Shifting everything out
+        let mut m = vm([100, 0, 70], &[0, 6]);
+        m.step().unwrap();
+        assert_eq!(m.a, 0);
+    }
+
+    #[test]
+    fn bxl() {
+        let mut m = vm([0, 0b101, 0], &[1, 3]);
+        m.step().unwrap();
+        assert_eq!(m.b, 0b110);
+    }
+
+    #[test]
+    fn bst() {
+        let mut m = vm([0o1234, 0, 0], &[2, 4]);
+        m.step().unwrap();
+        assert_eq!(m.b, 4);
+    }
+
+    #[test]
+    fn jnz() {
+        let mut m = vm([0, 0, 0], &[3, 6]);
+        m.step().unwrap();
+        assert_eq!(m.ip, 2);
+        let mut m = vm([1, 0, 0], &[3, 6]);
+        m.step().unwrap();
+        assert_eq!(m.ip, 6);
+        assert!(!m.step().unwrap());
+    }
+
+    #[test]
+    fn bxc() {
+        let mut m = vm([0, 6, 3], &[4, 7]);
+        m.step().unwrap();
+        assert_eq!(m.b, 5);
+    }
+
+    #[test]
+    fn out() {
+        let mut m = vm([0, 0, 13], &[5, 6, 5, 3]);
+        m.run().unwrap();
+        assert_eq!(m.output, [5, 3]);
+    }
+
+    #[test]
+    fn bdv_and_cdv() {
+        let mut m = vm([64, 0, 0], &[6, 3, 7, 2]);
+        m.step().unwrap();
+        assert_eq!((m.a, m.b, m.c), (64, 8, 0));
+        m.step().unwrap();
+        assert_eq!((m.a, m.b, m.c), (64, 8, 16));
+    }
+
+    #[test]
+    fn reserved_combo_operand() {
+        assert_eq!(
+            vm([0, 0, 0], &[1, 7, 5, 7]).run(),
+            Err("reserved combo operand 7 at 2".to_string())
+        );
+    }
+
+    #[test]
+    fn disassembly_and_trace() {
+        assert_eq!(
+            disassemble(&[0, 1, 5, 4, 3, 0]),
+            "  0: adv  A = A >> 1\n  2: out  output A & 7\n  4: jnz  if A != 0 goto 0"
+        );
+        let trace = vm([2, 0, 0], &[0, 1, 5, 4, 3, 0]).trace().unwrap();
+        assert_eq!(
+            trace,
+            [
+                "  0: adv  A = A >> 1          A=1 B=0 C=0",
+                "  2: out  output A & 7        A=1 B=0 C=0",
+                "  4: jnz  if A != 0 goto 0    A=1 B=0 C=0",
+                "  0: adv  A = A >> 1          A=0 B=0 C=0",
+                "  2: out  output A & 7        A=0 B=0 C=0",
+                "  4: jnz  if A != 0 goto 0    A=0 B=0 C=0",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_programs() {
+        assert_eq!(parse("Register A: 1").unwrap_err(), "no program");
+        assert_eq!(
+            parse("Program: 0,8").unwrap_err(),
+            "invalid 3-bit number \"8\""
+        );
+        assert_eq!(
+            parse("Register D: 1\nProgram: 0").unwrap_err(),
+            "unknown register \"D\""
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::grid::{distances, neighbours};
+use crate::solution::Solution;
+
+/// The memory grid bytes fall into, and how many have fallen when part 1 is asked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemorySpace {
+    pub width: usize,
+    pub height: usize,
+    pub fallen: usize,
+}
+
+impl MemorySpace {
+    /// The space of the puzzle example.
+    pub const EXAMPLE: MemorySpace = MemorySpace {
+        width: 7,
+        height: 7,
+        fallen: 12,
+    };
+    /// The space of the real puzzle inputs.
+    pub const REAL: MemorySpace = MemorySpace {
+        width: 71,
+        height: 71,
+        fallen: 1024,
+    };
+}
+
+/// Byte positions as `(x, y)`, in the order they fall.
+#[aoc_generator(day18)]
+pub fn parse(input: &str) -> Result<Vec<(usize, usize)>, String> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.trim()
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .ok_or_else(|| format!("invalid byte position {:?}", line))
+        })
+        .collect()
+}
+
+#[aoc(day18, part1)]
+fn day18_part1(bytes: &[(usize, usize)]) -> usize {
+    MemorySpace::REAL
+        .shortest_path(bytes)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .expect("the exit is unreachable")
+}
+
+#[aoc(day18, part2)]
+fn day18_part2(bytes: &[(usize, usize)]) -> String {
+    let (x, y) = MemorySpace::REAL
+        .first_blocking(bytes)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .expect("no byte blocks the exit");
+    format!("{},{}", x, y)
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    type Input = Vec<(usize, usize)>;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day18_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> String {
+        day18_part2(input)
+    }
+}
+
+impl MemorySpace {
+    /// Grid size as `(rows, columns)`.
+    fn size(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    /// For every cell, the index of the first byte that falls on it. Fails if a byte falls
+    /// outside the space.
+    fn fall_times(&self, bytes: &[(usize, usize)]) -> Result<Vec<Vec<usize>>, String> {
+        let mut times = vec![vec![usize::MAX; self.width]; self.height];
+        for (i, &(x, y)) in bytes.iter().enumerate().rev() {
+            if x >= self.width || y >= self.height {
+                return Err(format!(
+                    "byte {} at {},{} is outside the {}x{} space",
+                    i + 1,
+                    x,
+                    y,
+                    self.width,
+                    self.height
+                ));
+            }
+            times[y][x] = i;
+        }
+        Ok(times)
+    }
+
+    /// Fewest steps from the top left corner to the bottom right once `fallen` bytes have
+    /// fallen, if the exit can still be reached. An empty space has no path.
+    pub fn shortest_path(&self, bytes: &[(usize, usize)]) -> Result<Option<usize>, String> {
+        if self.width == 0 || self.height == 0 {
+            return Ok(None);
+        }
+        let times = self.fall_times(bytes)?;
+        let dist = distances(self.size(), (0, 0), |(r, c)| times[r][c] >= self.fallen);
+        Ok(dist[self.height - 1][self.width - 1])
+    }
+
+    /// The first byte that cuts the exit off from the start, as `(x, y)`.
+    ///
+    /// Works backwards from the state after every byte has fallen, removing bytes in reverse
+    /// and joining each freed cell to its free neighbours until start and exit connect.
+    pub fn first_blocking(
+        &self,
+        bytes: &[(usize, usize)],
+    ) -> Result<Option<(usize, usize)>, String> {
+        if self.width == 0 || self.height == 0 {
+            return Ok(None);
+        }
+        let times = self.fall_times(bytes)?;
+        let index = |(r, c): (usize, usize)| r * self.width + c;
+        let (start, exit) = (index((0, 0)), index((self.height - 1, self.width - 1)));
+        let mut sets = UnionFind::new(self.width * self.height);
+        let mut free = vec![vec![false; self.width]; self.height];
+
+        let mut free_cell = |sets: &mut UnionFind, (r, c): (usize, usize)| {
+            free[r][c] = true;
+            for (nr, nc) in neighbours(self.size(), (r, c)) {
+                if free[nr][nc] {
+                    sets.union(index((r, c)), index((nr, nc)));
+                }
+            }
+        };
+        for (r, row) in times.iter().enumerate() {
+            for (c, &time) in row.iter().enumerate() {
+                if time == usize::MAX {
+                    free_cell(&mut sets, (r, c));
+                }
+            }
+        }
+        if sets.find(start) == sets.find(exit) {
+            return Ok(None);
+        }
+
+        for (i, &(x, y)) in bytes.iter().enumerate().rev() {
+            // Only the first byte on a cell blocks it
+            if times[y][x] != i {
+                continue;
+            }
+            free_cell(&mut sets, (y, x));
+            if sets.find(start) == sets.find(exit) {
+                return Ok(Some((x, y)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Disjoint sets of `0..n` with path halving and union by size.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        5,4
+        4,2
+        4,5
+        3,0
+        2,1
+        6,3
+        2,4
+        1,5
+        0,6
+        3,3
+        2,6
+        5,1
+        1,2
+        5,5
+        2,5
+        6,5
+        1,4
+        0,4
+        6,4
+        1,1
+        6,1
+        1,0
+        0,5
+        1,6
+        2,0
+    "};
+
+    #[test]
+    fn example_part1() {
+        let bytes = parse(EXAMPLE).unwrap();
+        assert_eq!(MemorySpace::EXAMPLE.shortest_path(&bytes), Ok(Some(22)));
+    }
+
+    #[test]
+    fn example_part2() {
+        let bytes = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            MemorySpace::EXAMPLE.first_blocking(&bytes),
+            Ok(Some((6, 1)))
+        );
+    }
+
+    #[test]
+    fn blocking_matches_repeated_search() {
+        let bytes = parse(EXAMPLE).unwrap();
+        let blocking = (0..=bytes.len()).find(|&n| {
+            let space = MemorySpace {
+                fallen: n,
+                ..MemorySpace::EXAMPLE
+            };
+            space.shortest_path(&bytes).unwrap().is_none()
+        });
+        assert_eq!(blocking.map(|n| bytes[n - 1]), Some((6, 1)));
+
+        // A wall that never closes, and a repeated byte that doesn't block anything new
+        let space = MemorySpace {
+            width: 3,
+            height: 3,
+            fallen: 0,
+        };
+        assert_eq!(space.first_blocking(&[(1, 0), (1, 1)]), Ok(None));
+        assert_eq!(
+            space.first_blocking(&[(1, 0), (1, 0), (1, 1), (1, 2)]),
+            Ok(Some((1, 2)))
+        );
+        assert_eq!(space.first_blocking(&[(2, 2)]), Ok(Some((2, 2))));
+    }
+
+    #[test]
+    fn bytes_outside_the_space() {
+        let error = "byte 2 at 3,0 is outside the 3x3 space";
+        let space = MemorySpace {
+            width: 3,
+            height: 3,
+            fallen: 1,
+        };
+        assert_eq!(space.shortest_path(&[(0, 1), (3, 0)]).unwrap_err(), error);
+        assert_eq!(space.first_blocking(&[(0, 1), (3, 0)]).unwrap_err(), error);
+
+        for empty in [
+            MemorySpace { width: 0, ..space },
+            MemorySpace { height: 0, ..space },
+        ] {
+            assert_eq!(empty.shortest_path(&[(0, 0)]), Ok(None));
+            assert_eq!(empty.first_blocking(&[(0, 0)]), Ok(None));
+        }
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert_eq!(
+            parse("1,2\n3;4").unwrap_err(),
+            "invalid byte position \"3;4\""
+        );
+    }
+}
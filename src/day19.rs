@@ -0,0 +1,212 @@
+use crate::solution::Solution;
+
+/// Towel patterns and the designs to build from them.
+#[derive(Clone, Debug)]
+pub struct Onsen {
+    pub patterns: Trie,
+    pub designs: Vec<String>,
+}
+
+#[aoc_generator(day19)]
+pub fn parse(input: &str) -> Result<Onsen, String> {
+    let (patterns, designs) = input
+        .split_once("\n\n")
+        .ok_or("expected patterns and designs separated by a blank line")?;
+    let patterns: Vec<&str> = patterns.split(',').map(str::trim).collect();
+    if let Some(empty) = patterns.iter().position(|pattern| pattern.is_empty()) {
+        return Err(format!("pattern {} is empty", empty + 1));
+    }
+    Ok(Onsen {
+        patterns: Trie::new(patterns),
+        designs: designs
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+#[aoc(day19, part1)]
+fn day19_part1(onsen: &Onsen) -> usize {
+    onsen
+        .designs
+        .iter()
+        .filter(|design| onsen.patterns.arrangements(design) > 0)
+        .count()
+}
+
+#[aoc(day19, part2)]
+fn day19_part2(onsen: &Onsen) -> u64 {
+    onsen
+        .designs
+        .iter()
+        .map(|design| onsen.patterns.arrangements(design))
+        .sum()
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+
+    type Input = Onsen;
+    type Answer1 = usize;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day19_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        day19_part2(input)
+    }
+}
+
+/// A prefix tree of words, for finding every word a text starts with in one pass.
+#[derive(Clone, Debug)]
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    children: Vec<(u8, usize)>,
+    /// Whether a word ends here.
+    word: bool,
+}
+
+impl Trie {
+    pub fn new<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut trie = Trie {
+            nodes: vec![Node::default()],
+        };
+        for word in words {
+            trie.insert(word);
+        }
+        trie
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        let mut node = 0;
+        for &byte in word.as_bytes() {
+            node = match self.child(node, byte) {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.push((byte, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].word = true;
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|&&(b, _)| b == byte)
+            .map(|&(_, child)| child)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.prefix_lengths(word).any(|len| len == word.len())
+    }
+
+    /// Lengths of the words `text` starts with, shortest first.
+    pub fn prefix_lengths<'a>(&'a self, text: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.byte_prefix_lengths(text.as_bytes())
+    }
+
+    fn byte_prefix_lengths<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        text.iter()
+            .scan(0, move |node, &byte| {
+                *node = self.child(*node, byte)?;
+                Some(self.nodes[*node].word)
+            })
+            .enumerate()
+            .filter(|&(_, word)| word)
+            .map(|(i, _)| i + 1)
+    }
+
+    /// Number of ways to write `text` as a sequence of words, 0 if there are none.
+    pub fn arrangements(&self, text: &str) -> u64 {
+        let text = text.as_bytes();
+        // ways[i] counts the arrangements of text[i..]
+        let mut ways = vec![0u64; text.len() + 1];
+        ways[text.len()] = 1;
+        for i in (0..text.len()).rev() {
+            ways[i] = self
+                .byte_prefix_lengths(&text[i..])
+                .map(|len| ways[i + len])
+                .sum();
+        }
+        ways[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        r, wr, b, g, bwu, rb, gb, br
+
+        brwrr
+        bggr
+        gbbr
+        rrbgbr
+        ubwu
+        bwurrg
+        brgr
+        bbrgwb
+    "};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(day19_part1(&parse(EXAMPLE).unwrap()), 6);
+    }
+
+    #[test]
+    fn example_part2() {
+        let onsen = parse(EXAMPLE).unwrap();
+        let counts: Vec<u64> = onsen
+            .designs
+            .iter()
+            .map(|design| onsen.patterns.arrangements(design))
+            .collect();
+        assert_eq!(counts, [2, 1, 4, 6, 0, 1, 2, 0]);
+        assert_eq!(day19_part2(&onsen), 16);
+    }
+
+    #[test]
+    fn trie() {
+        let trie = Trie::new(["b", "br", "bwu", "r"]);
+        assert_eq!(trie.prefix_lengths("bwurr").collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(trie.prefix_lengths("brr").collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(trie.prefix_lengths("wb").count(), 0);
+        assert!(trie.contains("br"));
+        assert!(!trie.contains("bw"));
+        assert_eq!(trie.arrangements(""), 1);
+
+        // Counts far beyond what enumerating arrangements could reach
+        let trie = Trie::new(["a", "aa"]);
+        assert_eq!(trie.arrangements(&"a".repeat(90)), 4660046610375530309);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            parse("r, b\nbr").unwrap_err(),
+            "expected patterns and designs separated by a blank line"
+        );
+        assert_eq!(parse("r, , b\n\nbr").unwrap_err(), "pattern 2 is empty");
+    }
+}
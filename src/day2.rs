@@ -1,3 +1,5 @@
+use crate::solution::Solution;
+
 #[aoc_generator(day2)]
 fn parse(input: &str) -> Vec<Vec<i32>> {
     input
@@ -41,6 +43,28 @@ fn day2_part2(reports: &[Vec<i32>]) -> usize {
         .count()
 }
 
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<Vec<i32>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day2_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day2_part2(input)
+    }
+}
+
 fn is_safe_with_dampener(report: &[i32]) -> bool {
     // Check if the report is safe without modifications
     if is_safe(report) {
@@ -63,6 +87,7 @@ fn is_safe_with_dampener(report: &[i32]) -> bool {
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn part1_example() {
@@ -91,4 +116,35 @@ mod tests {
         let reports = parse(SAMPLE);
         assert_eq!(day2_part2(&reports), 4);
     }
+
+    fn reference_safe(levels: &[i32]) -> bool {
+        let steps: Vec<i32> = levels.windows(2).map(|w| w[1] - w[0]).collect();
+        levels.len() >= 2
+            && (steps.iter().all(|s| (1..=3).contains(s))
+                || steps.iter().all(|s| (-3..=-1).contains(s)))
+    }
+
+    /// Tries the report as is and with every single level removed.
+    fn reference_safe_with_dampener(levels: &[i32]) -> bool {
+        (0..=levels.len()).any(|skip| {
+            let kept: Vec<i32> = levels
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != skip)
+                .map(|(_, &level)| level)
+                .collect();
+            reference_safe(&kept)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(levels in prop::collection::vec(0i32..12, 0..8)) {
+            prop_assert_eq!(is_safe(&levels), reference_safe(&levels));
+            prop_assert_eq!(
+                is_safe_with_dampener(&levels),
+                reference_safe_with_dampener(&levels)
+            );
+        }
+    }
 }
@@ -0,0 +1,197 @@
+use crate::grid::{distances, offset};
+use crate::solution::Solution;
+use std::collections::BTreeMap;
+
+/// How far a cheat may go through walls in each part.
+pub const SHORT_CHEAT: usize = 2;
+pub const LONG_CHEAT: usize = 20;
+
+/// The saving a cheat needs for the real inputs' answers to count it.
+pub const MIN_SAVING: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct Racetrack {
+    /// Steps from the start to every track cell.
+    pub from_start: Vec<Vec<Option<usize>>>,
+    /// Steps from every track cell to the end.
+    pub to_end: Vec<Vec<Option<usize>>>,
+    /// Length of the race without cheating.
+    pub length: usize,
+}
+
+#[aoc_generator(day20)]
+pub fn parse(input: &str) -> Result<Racetrack, String> {
+    let grid = crate::grid::parse_chars(input)?;
+    let find = |tile: char| {
+        grid.iter()
+            .enumerate()
+            .find_map(|(r, row)| Some((r, row.iter().position(|&cell| cell == tile)?)))
+            .ok_or_else(|| format!("no {} on the racetrack", tile))
+    };
+    let (start, end) = (find('S')?, find('E')?);
+
+    let size = (grid.len(), grid[0].len());
+    let track = |(r, c): (usize, usize)| grid[r][c] != '#';
+    let from_start = distances(size, start, track);
+    let to_end = distances(size, end, track);
+    let length = from_start[end.0][end.1].ok_or("the end can't be reached")?;
+    Ok(Racetrack {
+        from_start,
+        to_end,
+        length,
+    })
+}
+
+#[aoc(day20, part1)]
+fn day20_part1(track: &Racetrack) -> usize {
+    track.count_cheats(SHORT_CHEAT, MIN_SAVING)
+}
+
+#[aoc(day20, part2)]
+fn day20_part2(track: &Racetrack) -> usize {
+    track.count_cheats(LONG_CHEAT, MIN_SAVING)
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+
+    type Input = Racetrack;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day20_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day20_part2(input)
+    }
+}
+
+impl Racetrack {
+    /// Time saved by every cheat of at most `radius` steps, where a cheat is identified by
+    /// the track cells it starts and ends on.
+    pub fn cheats(&self, radius: usize) -> impl Iterator<Item = usize> + '_ {
+        let size = (self.from_start.len(), self.from_start[0].len());
+        let radius = radius as isize;
+        let cells = (0..size.0).flat_map(move |r| (0..size.1).map(move |c| (r, c)));
+        cells
+            .filter_map(|(r, c)| Some(((r, c), self.from_start[r][c]?)))
+            .flat_map(move |(from, before)| {
+                (-radius..=radius).flat_map(move |dr| {
+                    let reach = radius - dr.abs();
+                    (-reach..=reach).filter_map(move |dc| {
+                        let (r, c) = offset(size, from, (dr, dc))?;
+                        let after = self.to_end[r][c]?;
+                        let total = before + (dr.abs() + dc.abs()) as usize + after;
+                        self.length.checked_sub(total).filter(|&saving| saving > 0)
+                    })
+                })
+            })
+    }
+
+    /// Number of cheats of at most `radius` steps that save at least `min_saving`.
+    pub fn count_cheats(&self, radius: usize, min_saving: usize) -> usize {
+        self.cheats(radius)
+            .filter(|&saving| saving >= min_saving)
+            .count()
+    }
+
+    /// How many cheats of at most `radius` steps save each amount of time.
+    pub fn savings(&self, radius: usize) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for saving in self.cheats(radius) {
+            *histogram.entry(saving).or_insert(0) += 1;
+        }
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        ###############
+        #...#...#.....#
+        #.#.#.#.#.###.#
+        #S#...#.#.#...#
+        #######.#.#.###
+        #######.#.#...#
+        #######.#.###.#
+        ###..E#...#...#
+        ###.#######.###
+        #...###...#...#
+        #.#####.#.###.#
+        #.#...#.#.#...#
+        #.#.#.#.#.#.###
+        #...#...#...###
+        ###############
+    "};
+
+    #[test]
+    fn example_part1() {
+        let track = parse(EXAMPLE).unwrap();
+        assert_eq!(track.length, 84);
+        assert_eq!(
+            track.savings(SHORT_CHEAT),
+            BTreeMap::from([
+                (2, 14),
+                (4, 14),
+                (6, 2),
+                (8, 4),
+                (10, 2),
+                (12, 3),
+                (20, 1),
+                (36, 1),
+                (38, 1),
+                (40, 1),
+                (64, 1),
+            ])
+        );
+        assert_eq!(track.count_cheats(SHORT_CHEAT, 20), 5);
+    }
+
+    #[test]
+    fn example_part2() {
+        let track = parse(EXAMPLE).unwrap();
+        let at_least_50: BTreeMap<usize, usize> = track
+            .savings(LONG_CHEAT)
+            .into_iter()
+            .filter(|&(saving, _)| saving >= 50)
+            .collect();
+        assert_eq!(
+            at_least_50,
+            BTreeMap::from([
+                (50, 32),
+                (52, 31),
+                (54, 29),
+                (56, 39),
+                (58, 25),
+                (60, 23),
+                (62, 20),
+                (64, 19),
+                (66, 12),
+                (68, 14),
+                (70, 12),
+                (72, 22),
+                (74, 4),
+                (76, 3),
+            ])
+        );
+        assert_eq!(track.count_cheats(LONG_CHEAT, 50), 285);
+    }
+
+    #[test]
+    fn invalid_tracks() {
+        assert_eq!(parse("#S.#").unwrap_err(), "no E on the racetrack");
+        assert_eq!(parse("#S#E#").unwrap_err(), "the end can't be reached");
+    }
+}
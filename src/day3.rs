@@ -1,3 +1,4 @@
+use crate::solution::Solution;
 use regex::Regex;
 
 #[aoc(day3, part1)]
@@ -45,10 +46,33 @@ fn day3_part2(input: &str) -> i32 {
     total
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    type Input = String;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> i32 {
+        day3_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> i32 {
+        day3_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn part1_example() {
@@ -65,4 +89,61 @@ mod tests {
         "};
         assert_eq!(day3_part2(INPUT), 48); // 2*4 + 8*5 = 48
     }
+
+    /// Scans for instructions by hand instead of with a regex.
+    fn reference_sum(memory: &str, conditional: bool) -> i32 {
+        fn number(s: &str) -> Option<(i32, &str)> {
+            let len = s.bytes().take_while(u8::is_ascii_digit).count();
+            if (1..=3).contains(&len) {
+                Some((s[..len].parse().unwrap(), &s[len..]))
+            } else {
+                None
+            }
+        }
+        let mut enabled = true;
+        let mut total = 0;
+        for i in 0..memory.len() {
+            let rest = &memory[i..];
+            if rest.starts_with("do()") {
+                enabled = true;
+            } else if rest.starts_with("don't()") {
+                enabled = false;
+            } else if let Some(args) = rest.strip_prefix("mul(") {
+                let Some((x, args)) = number(args) else {
+                    continue;
+                };
+                let Some(args) = args.strip_prefix(',') else {
+                    continue;
+                };
+                let Some((y, args)) = number(args) else {
+                    continue;
+                };
+                if args.starts_with(')') && (enabled || !conditional) {
+                    total += x * y;
+                }
+            }
+        }
+        total
+    }
+
+    fn memory() -> impl Strategy<Value = String> {
+        let token = prop_oneof![
+            Just("mul(".to_string()),
+            Just("do()".to_string()),
+            Just("don't()".to_string()),
+            Just(",".to_string()),
+            Just(")".to_string()),
+            Just("x".to_string()),
+            "[0-9]{1,4}",
+        ];
+        prop::collection::vec(token, 0..40).prop_map(|tokens| tokens.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(memory in memory()) {
+            prop_assert_eq!(day3_part1(&memory), reference_sum(&memory, false));
+            prop_assert_eq!(day3_part2(&memory), reference_sum(&memory, true));
+        }
+    }
 }
@@ -1,10 +1,10 @@
+use crate::solution::Solution;
+
 #[aoc_generator(day4)]
-fn parse(input: &str) -> Vec<Vec<char>> {
-    input
-        .lines()
-        .map(|line| line.chars().collect()) // Convert each line into a vector of characters
-        .collect() // Collect all lines into a Vec<Vec<char>>
+fn parse(input: &str) -> Result<Vec<Vec<char>>, String> {
+    crate::grid::parse_chars(input)
 }
+
 #[aoc(day4, part1)]
 fn day4_part1(grid: &[Vec<char>]) -> usize {
     // Directions represented as (dx, dy)
@@ -20,9 +20,8 @@ fn day4_part1(grid: &[Vec<char>]) -> usize {
     ];
 
     let rows = grid.len();
-    let cols = grid[0].len();
+    let cols = grid.first().map_or(0, Vec::len);
     let target = ['X', 'M', 'A', 'S'];
-    let word_length = target.len();
     let mut count = 0;
 
     for r in 0..rows {
@@ -30,7 +29,7 @@ fn day4_part1(grid: &[Vec<char>]) -> usize {
             // Try each direction
             for &(dx, dy) in &directions {
                 let mut found = true;
-                for i in 0..word_length {
+                for (i, &ch) in target.iter().enumerate() {
                     let nr = r as isize + i as isize * dx;
                     let nc = c as isize + i as isize * dy;
                     // Check bounds and match characters
@@ -38,7 +37,7 @@ fn day4_part1(grid: &[Vec<char>]) -> usize {
                         found = false;
                         break;
                     }
-                    if grid[nr as usize][nc as usize] != target[i] {
+                    if grid[nr as usize][nc as usize] != ch {
                         found = false;
                         break;
                     }
@@ -57,7 +56,12 @@ fn day4_part1(grid: &[Vec<char>]) -> usize {
 fn day4_part2(grid: &[Vec<char>]) -> usize {
     let mut count = 0;
     let rows = grid.len();
-    let cols = grid[0].len();
+    let cols = grid.first().map_or(0, Vec::len);
+
+    // An X-MAS needs at least a 3x3 window
+    if rows < 3 || cols < 3 {
+        return 0;
+    }
 
     // Check for X-MAS centered at (i, j)
     for i in 1..rows - 1 {
@@ -76,6 +80,28 @@ fn day4_part2(grid: &[Vec<char>]) -> usize {
     count
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input = Vec<Vec<char>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day4_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day4_part2(input)
+    }
+}
+
 fn is_xmas(diag1: (char, char, char), diag2: (char, char, char)) -> bool {
     // Check if the diagonals form an X-MAS
     let mas = ('M', 'A', 'S');
@@ -87,6 +113,7 @@ fn is_xmas(diag1: (char, char, char), diag2: (char, char, char)) -> bool {
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn part1_example() {
@@ -103,7 +130,7 @@ mod tests {
             MXMXAXMASX"
         };
 
-        assert_eq!(day4_part1(&parse(&SAMPLE)), 18);
+        assert_eq!(day4_part1(&parse(SAMPLE).unwrap()), 18);
     }
 
     #[test]
@@ -121,6 +148,118 @@ mod tests {
             MXMXAXMASX"
         };
 
-        assert_eq!(day4_part2(&parse(&SAMPLE)), 9);
+        assert_eq!(day4_part2(&parse(SAMPLE).unwrap()), 9);
+    }
+
+    #[test]
+    fn empty_grid() {
+        let grid = parse("").unwrap();
+        assert!(grid.is_empty());
+        assert_eq!(day4_part1(&grid), 0);
+        assert_eq!(day4_part2(&grid), 0);
+    }
+
+    #[test]
+    fn single_row() {
+        let grid = parse("XMASAMX").unwrap();
+        assert_eq!(day4_part1(&grid), 2);
+        assert_eq!(day4_part2(&grid), 0);
+    }
+
+    #[test]
+    fn single_column() {
+        let grid = parse(indoc! {
+            "X
+            M
+            A
+            S"
+        })
+        .unwrap();
+        assert_eq!(day4_part1(&grid), 1);
+        assert_eq!(day4_part2(&grid), 0);
+    }
+
+    #[test]
+    fn ragged_grid_is_rejected() {
+        let err = parse(indoc! {
+            "MMMS
+            MSAM
+            AMX"
+        })
+        .unwrap_err();
+        assert!(err.contains("row 3"), "{}", err);
+
+        let err = parse(indoc! {
+            "MMMS
+            MSAMX
+            AMXS"
+        })
+        .unwrap_err();
+        assert!(err.contains("row 2"), "{}", err);
+    }
+
+    /// Counts XMAS in every row, column and diagonal read as a string, both ways.
+    fn reference_xmas(grid: &[Vec<char>]) -> usize {
+        let (rows, cols) = (grid.len() as isize, grid[0].len() as isize);
+        let mut lines = Vec::new();
+        for (dr, dc) in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+            for r in 0..rows {
+                for c in 0..cols {
+                    // Only start at cells with no predecessor along the direction
+                    let (pr, pc) = (r - dr, c - dc);
+                    if (0..rows).contains(&pr) && (0..cols).contains(&pc) {
+                        continue;
+                    }
+                    let mut line = String::new();
+                    let (mut r, mut c) = (r, c);
+                    while (0..rows).contains(&r) && (0..cols).contains(&c) {
+                        line.push(grid[r as usize][c as usize]);
+                        r += dr;
+                        c += dc;
+                    }
+                    lines.push(line);
+                }
+            }
+        }
+        lines
+            .iter()
+            .map(|line| line.matches("XMAS").count() + line.matches("SAMX").count())
+            .sum()
+    }
+
+    /// Compares every 3x3 window against the four X-MAS layouts.
+    fn reference_x_mas(grid: &[Vec<char>]) -> usize {
+        let layouts = ["M.S.A.M.S", "M.M.A.S.S", "S.M.A.S.M", "S.S.A.M.M"];
+        let mut count = 0;
+        for r in 0..grid.len().saturating_sub(2) {
+            for c in 0..grid[0].len().saturating_sub(2) {
+                let window: Vec<char> = (0..9).map(|i| grid[r + i / 3][c + i % 3]).collect();
+                count += layouts
+                    .iter()
+                    .filter(|layout| {
+                        layout
+                            .chars()
+                            .zip(&window)
+                            .all(|(want, &have)| want == '.' || want == have)
+                    })
+                    .count();
+            }
+        }
+        count
+    }
+
+    fn grid() -> impl Strategy<Value = Vec<Vec<char>>> {
+        (1usize..8, 1usize..8).prop_flat_map(|(rows, cols)| {
+            let cell = prop::sample::select(vec!['X', 'M', 'A', 'S', '.']);
+            prop::collection::vec(prop::collection::vec(cell, cols), rows)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(grid in grid()) {
+            prop_assert_eq!(day4_part1(&grid), reference_xmas(&grid));
+            prop_assert_eq!(day4_part2(&grid), reference_x_mas(&grid));
+        }
     }
 }
@@ -1,3 +1,4 @@
+use crate::solution::Solution;
 use std::collections::{HashMap, VecDeque};
 
 #[aoc_generator(day5)]
@@ -71,7 +72,29 @@ fn day5_part2(input: &(Vec<(usize, usize)>, Vec<Vec<usize>>)) -> usize {
         .sum()
 }
 
-fn is_valid_update(update: &Vec<usize>, adj_list: &HashMap<usize, Vec<usize>>) -> bool {
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    type Input = (Vec<(usize, usize)>, Vec<Vec<usize>>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day5_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day5_part2(input)
+    }
+}
+
+fn is_valid_update(update: &[usize], adj_list: &HashMap<usize, Vec<usize>>) -> bool {
     // For each pair of pages in the update, check if the ordering rules are respected
     for (before, after) in adj_list
         .iter()
@@ -90,7 +113,7 @@ fn is_valid_update(update: &Vec<usize>, adj_list: &HashMap<usize, Vec<usize>>) -
     true // All rules are satisfied
 }
 
-fn reorder_update(update: &Vec<usize>, adj_list: &HashMap<usize, Vec<usize>>) -> Vec<usize> {
+fn reorder_update(update: &[usize], adj_list: &HashMap<usize, Vec<usize>>) -> Vec<usize> {
     // Build a local in-degree map and adjacency list for the current update
     let mut local_in_degree: HashMap<usize, usize> = HashMap::new();
     let mut local_adj_list: HashMap<usize, Vec<usize>> = HashMap::new();
@@ -144,6 +167,7 @@ fn reorder_update(update: &Vec<usize>, adj_list: &HashMap<usize, Vec<usize>>) ->
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn part1_example() {
@@ -213,4 +237,61 @@ mod tests {
         };
         assert_eq!(day5_part2(&parse(input)), 123);
     }
+
+    /// Rules for every pair of pages in `order`, and updates built from picks into it.
+    fn rules_and_updates() -> impl Strategy<Value = (Vec<usize>, Vec<Vec<usize>>)> {
+        let order = Just((10..19).collect::<Vec<usize>>()).prop_shuffle();
+        let picks = prop::collection::vec(prop::collection::vec(0usize..9, 1..8), 1..6);
+        (order, picks).prop_map(|(order, picks)| {
+            let updates = picks
+                .into_iter()
+                .map(|picks| {
+                    let mut update: Vec<usize> = Vec::new();
+                    for i in picks {
+                        if !update.contains(&order[i]) {
+                            update.push(order[i]);
+                        }
+                    }
+                    // Updates always have a middle page
+                    if update.len() & 1 == 0 {
+                        update.pop();
+                    }
+                    update
+                })
+                .collect();
+            (order, updates)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference((order, updates) in rules_and_updates()) {
+            let mut rules = Vec::new();
+            for (i, &a) in order.iter().enumerate() {
+                for &b in &order[i + 1..] {
+                    rules.push((a, b));
+                }
+            }
+            let rank = |page: &usize| order.iter().position(|p| p == page).unwrap();
+
+            // An update is in order unless some later page must come before an earlier one
+            let (mut valid, mut fixed) = (0, 0);
+            for update in &updates {
+                let in_order = update.iter().enumerate().all(|(i, a)| {
+                    update[i + 1..].iter().all(|b| !rules.contains(&(*b, *a)))
+                });
+                let mut sorted = update.clone();
+                sorted.sort_by_key(rank);
+                if in_order {
+                    valid += update[update.len() / 2];
+                } else {
+                    fixed += sorted[sorted.len() / 2];
+                }
+            }
+
+            let input = (rules, updates);
+            prop_assert_eq!(day5_part1(&input), valid);
+            prop_assert_eq!(day5_part2(&input), fixed);
+        }
+    }
 }
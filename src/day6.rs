@@ -1,7 +1,8 @@
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir {
     Up,
     Right,
     Down,
@@ -17,24 +18,9 @@ fn parse(input: &str) -> (Vec<Vec<char>>, (usize, usize), Dir) {
 
     for (i, row) in map.iter().enumerate() {
         for (j, &cell) in row.iter().enumerate() {
-            match cell {
-                '^' => {
-                    start_pos = (i, j);
-                    start_dir = Dir::Up;
-                }
-                'v' => {
-                    start_pos = (i, j);
-                    start_dir = Dir::Down;
-                }
-                '<' => {
-                    start_pos = (i, j);
-                    start_dir = Dir::Left;
-                }
-                '>' => {
-                    start_pos = (i, j);
-                    start_dir = Dir::Right;
-                }
-                _ => {}
+            if let Some(dir) = dir_from_arrow(cell) {
+                start_pos = (i, j);
+                start_dir = dir;
             }
         }
     }
@@ -80,8 +66,30 @@ fn day6_part2(input: &(Vec<Vec<char>>, (usize, usize), Dir)) -> usize {
     count
 }
 
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    type Input = (Vec<Vec<char>>, (usize, usize), Dir);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day6_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day6_part2(input)
+    }
+}
+
 fn find_candidate_cells_fast(
-    map: &Vec<Vec<char>>,
+    map: &[Vec<char>],
     start_pos: (usize, usize),
     start_dir: Dir,
 ) -> Vec<(usize, usize)> {
@@ -120,11 +128,7 @@ fn find_candidate_cells_fast(
 }
 
 /// Simulates the guard's patrol until leaving the map. Returns the number of visited positions.
-fn simulate_count_visited(
-    map: &Vec<Vec<char>>,
-    start_pos: (usize, usize),
-    start_dir: Dir,
-) -> usize {
+fn simulate_count_visited(map: &[Vec<char>], start_pos: (usize, usize), start_dir: Dir) -> usize {
     let mut visited = HashSet::new();
     let mut position = start_pos;
     let mut direction = start_dir;
@@ -159,7 +163,7 @@ fn simulate_count_visited(
 
     visited.len()
 }
-fn causes_loop(map: &Vec<Vec<char>>, start_pos: (usize, usize), start_dir: Dir) -> bool {
+fn causes_loop(map: &[Vec<char>], start_pos: (usize, usize), start_dir: Dir) -> bool {
     let mut slow = (start_pos, start_dir);
     let mut fast = match advance(map, start_pos, start_dir) {
         Some(state) => state,
@@ -196,7 +200,7 @@ fn causes_loop(map: &Vec<Vec<char>>, start_pos: (usize, usize), start_dir: Dir)
 
 /// Advance one step in the simulation
 fn advance(
-    map: &Vec<Vec<char>>,
+    map: &[Vec<char>],
     position: (usize, usize),
     direction: Dir,
 ) -> Option<((usize, usize), Dir)> {
@@ -220,7 +224,8 @@ fn advance(
     }
 }
 
-fn dir_to_delta(d: Dir) -> (isize, isize) {
+/// Row and column offsets of one step in a direction.
+pub fn dir_to_delta(d: Dir) -> (isize, isize) {
     match d {
         Dir::Up => (-1, 0),
         Dir::Right => (0, 1),
@@ -229,7 +234,7 @@ fn dir_to_delta(d: Dir) -> (isize, isize) {
     }
 }
 
-fn turn_right(d: Dir) -> Dir {
+pub fn turn_right(d: Dir) -> Dir {
     match d {
         Dir::Up => Dir::Right,
         Dir::Right => Dir::Down,
@@ -238,10 +243,31 @@ fn turn_right(d: Dir) -> Dir {
     }
 }
 
+pub fn turn_left(d: Dir) -> Dir {
+    match d {
+        Dir::Up => Dir::Left,
+        Dir::Left => Dir::Down,
+        Dir::Down => Dir::Right,
+        Dir::Right => Dir::Up,
+    }
+}
+
+/// The direction an arrow such as `^` or `>` points in.
+pub fn dir_from_arrow(c: char) -> Option<Dir> {
+    match c {
+        '^' => Some(Dir::Up),
+        '>' => Some(Dir::Right),
+        'v' => Some(Dir::Down),
+        '<' => Some(Dir::Left),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn example_part1() {
@@ -276,4 +302,67 @@ mod tests {
         };
         assert_eq!(day6_part2(&parse(input)), 6);
     }
+
+    /// Walks the guard step by step; `None` if it ends up in a loop.
+    fn reference_walk(map: &[Vec<char>], start: (usize, usize)) -> Option<HashSet<(usize, usize)>> {
+        let (mut r, mut c) = (start.0 as isize, start.1 as isize);
+        let (mut dr, mut dc) = (-1, 0);
+        let mut states = HashSet::new();
+        loop {
+            if !states.insert((r, c, dr, dc)) {
+                return None;
+            }
+            let (nr, nc) = (r + dr, c + dc);
+            match map.get(nr as usize).and_then(|row| row.get(nc as usize)) {
+                None => break,
+                Some('#') => (dr, dc) = (dc, -dr),
+                Some(_) => (r, c) = (nr, nc),
+            }
+        }
+        Some(
+            states
+                .iter()
+                .map(|&(r, c, _, _)| (r as usize, c as usize))
+                .collect(),
+        )
+    }
+
+    /// Tries an obstruction on every free cell.
+    fn reference_loops(map: &[Vec<char>], start: (usize, usize)) -> usize {
+        let mut loops = 0;
+        for r in 0..map.len() {
+            for c in 0..map[r].len() {
+                if map[r][c] == '.' {
+                    let mut blocked = map.to_vec();
+                    blocked[r][c] = '#';
+                    loops += reference_walk(&blocked, start).is_none() as usize;
+                }
+            }
+        }
+        loops
+    }
+
+    fn lab() -> impl Strategy<Value = (Vec<Vec<char>>, (usize, usize))> {
+        (1usize..8, 1usize..8).prop_flat_map(|(rows, cols)| {
+            let cell = prop::sample::select(vec!['.', '.', '.', '#']);
+            (
+                prop::collection::vec(prop::collection::vec(cell, cols), rows),
+                (0..rows, 0..cols),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference((mut map, start) in lab()) {
+            map[start.0][start.1] = '^';
+            let input: Vec<String> = map.iter().map(|row| row.iter().collect()).collect();
+            let input = parse(&input.join("\n"));
+            // Puzzle inputs always let the guard leave the lab
+            let visited = reference_walk(&map, start);
+            prop_assume!(visited.is_some());
+            prop_assert_eq!(day6_part1(&input), visited.unwrap().len());
+            prop_assert_eq!(day6_part2(&input), reference_loops(&map, start));
+        }
+    }
 }
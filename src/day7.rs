@@ -1,3 +1,5 @@
+use crate::solution::Solution;
+
 #[aoc_generator(day7)]
 fn parse(input: &str) -> Vec<(usize, Vec<usize>)> {
     input
@@ -32,6 +34,28 @@ fn day7_part2(equations: &[(usize, Vec<usize>)]) -> usize {
         .sum()
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    type Input = Vec<(usize, Vec<usize>)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        day7_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day7_part2(input)
+    }
+}
+
 /// Check if we can form the target by inserting '+' or '*' between the numbers
 fn can_form_target(target: usize, numbers: &[usize]) -> bool {
     if numbers.len() == 1 {
@@ -44,14 +68,14 @@ fn can_form_target(target: usize, numbers: &[usize]) -> bool {
 
     for mask in 0..combinations {
         let mut result = numbers[0];
-        for i in 1..n {
+        for (i, &next_val) in numbers.iter().enumerate().skip(1) {
             let op = (mask >> (i - 1)) & 1;
             if op == 0 {
                 // '+'
-                result = result + numbers[i];
+                result += next_val;
             } else {
                 // '*'
-                result = result * numbers[i];
+                result *= next_val;
             }
         }
 
@@ -75,19 +99,18 @@ fn can_form_target_with_concat(target: usize, numbers: &[usize]) -> bool {
     for mut mask in 0..combinations {
         let mut result = numbers[0];
 
-        for i in 1..n {
+        for &next_val in &numbers[1..] {
             let op = mask % 3;
             mask /= 3;
 
-            let next_val = numbers[i];
             match op {
                 0 => {
                     // '+'
-                    result = result + next_val;
+                    result += next_val;
                 }
                 1 => {
                     // '*'
-                    result = result * next_val;
+                    result *= next_val;
                 }
                 2 => {
                     // '||' concatenation
@@ -131,6 +154,7 @@ fn num_digits(mut x: usize) -> usize {
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn example_part1() {
@@ -166,4 +190,56 @@ mod tests {
         // making the total 11387.
         assert_eq!(day7_part2(&parse(input)), 11387);
     }
+
+    /// Tries every operator sequence recursively.
+    fn reference_reachable(target: usize, acc: usize, rest: &[usize], concat: bool) -> bool {
+        match rest.split_first() {
+            None => acc == target,
+            Some((&next, rest)) => {
+                reference_reachable(target, acc + next, rest, concat)
+                    || reference_reachable(target, acc * next, rest, concat)
+                    || (concat
+                        && reference_reachable(
+                            target,
+                            format!("{}{}", acc, next).parse().unwrap(),
+                            rest,
+                            concat,
+                        ))
+            }
+        }
+    }
+
+    /// Numbers with a target that is either random or the result of random operators.
+    fn equation() -> impl Strategy<Value = (usize, Vec<usize>)> {
+        (
+            prop::collection::vec((0usize..20, 0u8..3), 1..6),
+            0usize..2000,
+            any::<bool>(),
+        )
+            .prop_map(|(terms, random_target, use_random)| {
+                let numbers: Vec<usize> = terms.iter().map(|&(n, _)| n).collect();
+                let target = terms[1..]
+                    .iter()
+                    .fold(numbers[0], |acc, &(n, op)| match op {
+                        0 => acc + n,
+                        1 => acc * n,
+                        _ => format!("{}{}", acc, n).parse().unwrap(),
+                    });
+                (if use_random { random_target } else { target }, numbers)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference((target, numbers) in equation()) {
+            prop_assert_eq!(
+                can_form_target(target, &numbers),
+                reference_reachable(target, numbers[0], &numbers[1..], false)
+            );
+            prop_assert_eq!(
+                can_form_target_with_concat(target, &numbers),
+                reference_reachable(target, numbers[0], &numbers[1..], true)
+            );
+        }
+    }
 }
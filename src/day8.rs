@@ -1,14 +1,21 @@
-use std::collections::{HashMap, HashSet};
+use crate::solution::Solution;
+use std::collections::{BTreeMap, HashSet};
+
+/// An antenna's position and frequency. Positions are `[row, col]` on a flat map and
+/// `[row, col, layer]` in a layered field.
+pub type Antenna<const D: usize> = ([usize; D], char);
+
+pub type Input = (Vec<Vec<char>>, Vec<Antenna<2>>);
 
 #[aoc_generator(day8)]
-fn parse(input: &str) -> (Vec<Vec<char>>, Vec<(usize, usize, char)>) {
+pub fn parse(input: &str) -> Input {
     let map: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
     let mut antennas = Vec::new();
 
     for (r, row) in map.iter().enumerate() {
         for (c, &ch) in row.iter().enumerate() {
             if ch.is_ascii_alphanumeric() {
-                antennas.push((r, c, ch));
+                antennas.push(([r, c], ch));
             }
         }
     }
@@ -16,97 +23,278 @@ fn parse(input: &str) -> (Vec<Vec<char>>, Vec<(usize, usize, char)>) {
     (map, antennas)
 }
 
-#[aoc(day8, part1)]
-fn day8_part1(input: &(Vec<Vec<char>>, Vec<(usize, usize, char)>)) -> usize {
-    let (map, antennas) = input;
-    let rows = map.len();
-    let cols = map[0].len();
-
-    // Group antennas by frequency
-    let mut freq_map: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
-    for &(r, c, ch) in antennas {
-        freq_map.entry(ch).or_default().push((r, c));
-    }
-
-    let mut antinodes = HashSet::new();
-
-    // For each frequency group, consider all pairs of antennas
-    for (&freq, positions) in &freq_map {
-        let n = positions.len();
-        for i in 0..n {
-            for j in i + 1..n {
-                let (px, py) = positions[i];
-                let (qx, qy) = positions[j];
-
-                // Antinodes:
-                // A1 = 2Q - P
-                // A2 = 2P - Q
-                let a1x = 2 * qx as isize - px as isize;
-                let a1y = 2 * qy as isize - py as isize;
-                let a2x = 2 * px as isize - qx as isize;
-                let a2y = 2 * py as isize - qy as isize;
-
-                // Check if within bounds
-                if a1x >= 0 && a1x < rows as isize && a1y >= 0 && a1y < cols as isize {
-                    antinodes.insert((a1x as usize, a1y as usize));
-                }
-                if a2x >= 0 && a2x < rows as isize && a2y >= 0 && a2y < cols as isize {
-                    antinodes.insert((a2x as usize, a2y as usize));
-                }
-            }
+/// Parses a stack of equally sized layers, separated by blank lines, into a 3D antenna field.
+/// The first layer is layer 0.
+pub fn parse_layers(input: &str) -> Result<(BoxBounds<3>, Vec<Antenna<3>>), String> {
+    let layers: Vec<Input> = input.split("\n\n").map(parse).collect();
+    let rows = layers[0].0.len();
+    let cols = layers[0].0.first().map_or(0, Vec::len);
+    let mut antennas = Vec::new();
+
+    for (z, (map, layer_antennas)) in layers.iter().enumerate() {
+        if map.len() != rows || map.iter().any(|row| row.len() != cols) {
+            return Err(format!(
+                "layer {} is not {}x{} like the first layer",
+                z + 1,
+                rows,
+                cols
+            ));
         }
+        antennas.extend(layer_antennas.iter().map(|&([r, c], ch)| ([r, c, z], ch)));
     }
 
-    antinodes.len()
+    Ok((BoxBounds([rows, cols, layers.len()]), antennas))
 }
 
-#[aoc(day8, part2)]
-fn day8_part2(input: &(Vec<Vec<char>>, Vec<(usize, usize, char)>)) -> usize {
-    let (map, antennas) = input;
-    let rows = map.len();
-    let cols = map[0].len();
+/// The region antinodes may occupy.
+pub trait Bounds<const D: usize> {
+    /// Size of the box enclosing the region along each axis.
+    fn extent(&self) -> [usize; D];
+
+    /// Whether a position inside the extent belongs to the region.
+    fn contains(&self, pos: [usize; D]) -> bool;
+
+    /// Converts a computed position into an in-bounds one, if it is part of the region.
+    fn locate(&self, pos: [isize; D]) -> Option<[usize; D]> {
+        let extent = self.extent();
+        if pos
+            .iter()
+            .zip(&extent)
+            .any(|(&p, &len)| p < 0 || p >= len as isize)
+        {
+            return None;
+        }
+        let pos = pos.map(|p| p as usize);
+        self.contains(pos).then_some(pos)
+    }
+}
+
+/// An axis-aligned box starting at the origin: the map rectangle in 2D, stacked layers in 3D.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoxBounds<const D: usize>(pub [usize; D]);
 
-    // Group antennas by frequency
-    let mut freq_map: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
-    for &(r, c, ch) in antennas {
-        freq_map.entry(ch).or_default().push((r, c));
+impl BoxBounds<2> {
+    /// The rectangle covered by a map.
+    pub fn of_map(map: &[Vec<char>]) -> Self {
+        BoxBounds([map.len(), map.first().map_or(0, Vec::len)])
     }
+}
 
-    let mut antinodes = HashSet::new();
+impl<const D: usize> Bounds<D> for BoxBounds<D> {
+    fn extent(&self) -> [usize; D] {
+        self.0
+    }
 
-    for (&freq, positions) in &freq_map {
-        // If only one antenna for this freq, it can't form a line with another antenna
-        if positions.len() < 2 {
-            continue;
-        }
+    fn contains(&self, _pos: [usize; D]) -> bool {
+        true
+    }
+}
+
+/// A map rectangle with some cells carved out, e.g. `~` cells antinodes cannot occupy.
+pub struct Mask {
+    open: Vec<Vec<bool>>,
+    cols: usize,
+}
 
-        for i in 0..positions.len() {
-            for j in i + 1..positions.len() {
-                let (px, py) = positions[i];
-                let (qx, qy) = positions[j];
+impl Mask {
+    /// Marks every `blocked` cell of the map as out of bounds.
+    pub fn from_map(map: &[Vec<char>], blocked: char) -> Self {
+        let open = map
+            .iter()
+            .map(|row| row.iter().map(|&ch| ch != blocked).collect())
+            .collect();
+        let cols = map.first().map_or(0, Vec::len);
+        Mask { open, cols }
+    }
+}
+
+impl Bounds<2> for Mask {
+    fn extent(&self) -> [usize; 2] {
+        [self.open.len(), self.cols]
+    }
+
+    fn contains(&self, [r, c]: [usize; 2]) -> bool {
+        self.open[r].get(c).copied().unwrap_or(false)
+    }
+}
+
+/// Decides where a pair of same-frequency antennas produces antinodes.
+pub trait AntinodeModel {
+    /// Returns the in-bounds antinodes created by the antennas at `p` and `q`.
+    fn antinodes<const D: usize, B: Bounds<D>>(
+        &self,
+        p: [usize; D],
+        q: [usize; D],
+        bounds: &B,
+    ) -> Vec<[usize; D]>;
+}
+
+/// Part 1: one antinode on each side of the pair, twice as far from one antenna as the other.
+pub struct Paired;
+
+impl AntinodeModel for Paired {
+    fn antinodes<const D: usize, B: Bounds<D>>(
+        &self,
+        p: [usize; D],
+        q: [usize; D],
+        bounds: &B,
+    ) -> Vec<[usize; D]> {
+        // Antinodes:
+        // A1 = 2Q - P
+        // A2 = 2P - Q
+        let a1 = std::array::from_fn(|i| 2 * q[i] as isize - p[i] as isize);
+        let a2 = std::array::from_fn(|i| 2 * p[i] as isize - q[i] as isize);
+
+        // Keep only those within bounds
+        [a1, a2]
+            .into_iter()
+            .filter_map(|a| bounds.locate(a))
+            .collect()
+    }
+}
 
-                let dx = qx as isize - px as isize;
-                let dy = qy as isize - py as isize;
-                let g = gcd(dx.abs(), dy.abs());
-                let sx = dx / g;
-                let sy = dy / g;
+/// Part 2: every grid position exactly in line with the pair, including the antennas themselves.
+pub struct Resonant;
+
+impl AntinodeModel for Resonant {
+    fn antinodes<const D: usize, B: Bounds<D>>(
+        &self,
+        p: [usize; D],
+        q: [usize; D],
+        bounds: &B,
+    ) -> Vec<[usize; D]> {
+        let d: [isize; D] = std::array::from_fn(|i| q[i] as isize - p[i] as isize);
+        let g = d.iter().fold(0, |g, &di| gcd(g, di.abs()));
+        let s = d.map(|di| di / g);
+
+        // We now have a step s that represents the smallest move along this line.
+        // We want all points p + k*s within the bounds.
+
+        // Find the range of k values that keep the point inside the enclosing box,
+        // then let the bounds reject any holes in it:
+        let (k_min, k_max) = find_k_range(p, s, bounds.extent());
+
+        (k_min..=k_max)
+            .filter_map(|k| bounds.locate(std::array::from_fn(|i| p[i] as isize + k * s[i])))
+            .collect()
+    }
+}
 
-                // We now have a step (sx, sy) that represents the smallest move along this line.
-                // We want all points (px + k*sx, py + k*sy) within the map.
+/// Groups antenna positions by their frequency.
+fn group_by_frequency<const D: usize>(antennas: &[Antenna<D>]) -> BTreeMap<char, Vec<[usize; D]>> {
+    let mut freq_map: BTreeMap<char, Vec<[usize; D]>> = BTreeMap::new();
+    for &(pos, ch) in antennas {
+        freq_map.entry(ch).or_default().push(pos);
+    }
+    freq_map
+}
 
-                // Find the range of k values that keep the point inside the map:
-                let (k_min, k_max) = find_k_range(px, py, sx, sy, rows, cols);
+/// Antinodes of a map, broken down by the frequency whose antennas produced them.
+pub struct AntinodeMap<const D: usize> {
+    pub by_frequency: BTreeMap<char, HashSet<[usize; D]>>,
+}
 
-                for k in k_min..=k_max {
-                    let x = (px as isize + k * sx) as usize;
-                    let y = (py as isize + k * sy) as usize;
-                    antinodes.insert((x, y));
+impl<const D: usize> AntinodeMap<D> {
+    /// Collects the antinodes of every same-frequency antenna pair under the given model.
+    pub fn new<B: Bounds<D>, M: AntinodeModel>(
+        antennas: &[Antenna<D>],
+        bounds: &B,
+        model: &M,
+    ) -> Self {
+        let mut by_frequency = BTreeMap::new();
+
+        // For each frequency group, consider all pairs of antennas
+        for (freq, positions) in group_by_frequency(antennas) {
+            let mut antinodes = HashSet::new();
+            for (i, &p) in positions.iter().enumerate() {
+                for &q in &positions[i + 1..] {
+                    antinodes.extend(model.antinodes(p, q, bounds));
                 }
             }
+            by_frequency.insert(freq, antinodes);
+        }
+
+        AntinodeMap { by_frequency }
+    }
+
+    /// All unique antinode positions, regardless of frequency.
+    pub fn all(&self) -> HashSet<[usize; D]> {
+        self.by_frequency.values().flatten().copied().collect()
+    }
+
+    /// Positions that are antinodes of more than one frequency, with those frequencies.
+    pub fn overlap(&self) -> BTreeMap<[usize; D], Vec<char>> {
+        let mut freqs_at: BTreeMap<[usize; D], Vec<char>> = BTreeMap::new();
+        for (&freq, antinodes) in &self.by_frequency {
+            for &pos in antinodes {
+                freqs_at.entry(pos).or_default().push(freq);
+            }
         }
+        freqs_at.retain(|_, freqs| freqs.len() > 1);
+        freqs_at
+    }
+}
+
+impl AntinodeMap<2> {
+    /// Draws the antinodes as `#` over the original map, like the puzzle illustrations.
+    /// Antennas are left visible where an antinode coincides with them.
+    pub fn render(&self, map: &[Vec<char>]) -> String {
+        let all = self.all();
+        map.iter()
+            .enumerate()
+            .map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(c, &ch)| {
+                        if ch == '.' && all.contains(&[r, c]) {
+                            '#'
+                        } else {
+                            ch
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Collects the unique antinodes of the whole map rectangle under the given model.
+fn find_antinodes<M: AntinodeModel>(input: &Input, model: &M) -> HashSet<[usize; 2]> {
+    let (map, antennas) = input;
+    AntinodeMap::new(antennas, &BoxBounds::of_map(map), model).all()
+}
+
+#[aoc(day8, part1)]
+fn day8_part1(input: &Input) -> usize {
+    find_antinodes(input, &Paired).len()
+}
+
+#[aoc(day8, part2)]
+fn day8_part2(input: &Input) -> usize {
+    find_antinodes(input, &Resonant).len()
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
     }
 
-    antinodes.len()
+    fn part1(input: &Self::Input) -> usize {
+        day8_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        day8_part2(input)
+    }
 }
 
 fn gcd(a: isize, b: isize) -> isize {
@@ -117,97 +305,417 @@ fn gcd(a: isize, b: isize) -> isize {
     }
 }
 
-/// Given a starting point (px, py) and step (sx, sy), find the range of integer k
-/// such that the line point (px + k*sx, py + k*sy) remains inside [0..rows, 0..cols].
-fn find_k_range(
-    px: usize,
-    py: usize,
-    sx: isize,
-    sy: isize,
-    rows: usize,
-    cols: usize,
+/// Given a starting point p and step s, find the range of integer k such that the
+/// line point p + k*s remains inside the box [0..extent[0], 0..extent[1], ...].
+/// The range is empty when k_min > k_max.
+fn find_k_range<const D: usize>(
+    p: [usize; D],
+    s: [isize; D],
+    extent: [usize; D],
 ) -> (isize, isize) {
-    let px = px as isize;
-    let py = py as isize;
-    let (mut k_min_x, mut k_max_x) = (-std::isize::MAX, std::isize::MAX);
-    let (mut k_min_y, mut k_max_y) = (-std::isize::MAX, std::isize::MAX);
-
-    // For x dimension
-    if sx == 0 {
-        // No horizontal movement, check if px in range
-        if px < 0 || px >= rows as isize {
-            // Line does not intersect map at all
-            return (1, -1); // empty range
-        }
-        // No further k constraints from x
-    } else {
-        // 0 <= px + k*sx < rows
-        // => -px <= k*sx < rows - px
-        // Divide by sx considering sign
-        let min_val_x = -px;
-        let max_val_x = (rows - 1) as isize - px;
-
-        let (local_min_x, local_max_x) = if sx > 0 {
-            (ceil_div(min_val_x, sx), floor_div(max_val_x, sx))
-        } else {
-            // sx < 0
-            (ceil_div(max_val_x, sx), floor_div(min_val_x, sx))
-        };
+    let (mut k_min, mut k_max) = (-isize::MAX, isize::MAX);
 
-        k_min_x = local_min_x;
-        k_max_x = local_max_x;
-    }
+    for ((&p, &s), &len) in p.iter().zip(&s).zip(&extent) {
+        let p = p as isize;
+        let len = len as isize;
 
-    // For y dimension
-    if sy == 0 {
-        // No vertical movement, check if py in range
-        if py < 0 || py >= cols as isize {
-            return (1, -1); // no intersection
+        if s == 0 {
+            // No movement along this axis, check if p in range
+            if p >= len {
+                // Line does not intersect the box at all
+                return (1, -1); // empty range
+            }
+            // No further k constraints from this axis
+            continue;
         }
-        // No further constraints from y
-    } else {
-        // 0 <= py + k*sy < cols
-        // => -py <= k*sy < cols - py
-        let min_val_y = -py;
-        let max_val_y = (cols - 1) as isize - py;
 
-        let (local_min_y, local_max_y) = if sy > 0 {
-            (ceil_div(min_val_y, sy), floor_div(max_val_y, sy))
+        // 0 <= p + k*s < len
+        // => -p <= k*s < len - p
+        // Divide by s considering sign
+        let min_val = -p;
+        let max_val = len - 1 - p;
+
+        let (local_min, local_max) = if s > 0 {
+            (ceil_div(min_val, s), floor_div(max_val, s))
         } else {
-            (ceil_div(max_val_y, sy), floor_div(min_val_y, sy))
+            // s < 0
+            (ceil_div(max_val, s), floor_div(min_val, s))
         };
 
-        k_min_y = local_min_y;
-        k_max_y = local_max_y;
+        k_min = k_min.max(local_min);
+        k_max = k_max.min(local_max);
     }
 
-    let k_min = k_min_x.max(k_min_y);
-    let k_max = k_max_x.min(k_max_y);
-
     (k_min, k_max)
 }
 
 /// Integer division that rounds up for negative or positive values.
 /// ceil_div(a,b) = ceil(a/b)
 fn ceil_div(a: isize, b: isize) -> isize {
-    if (a ^ b) >= 0 {
-        // same sign
-        (a + b - 1) / b
+    let q = a / b;
+    // `/` truncates towards zero, which only rounds down for a positive, inexact quotient
+    if a % b != 0 && (a < 0) == (b < 0) {
+        q + 1
     } else {
-        // different sign
-        a / b
+        q
     }
 }
 
 /// Integer division that floors the result
+/// floor_div(a,b) = floor(a/b)
 fn floor_div(a: isize, b: isize) -> isize {
-    a / b
+    let q = a / b;
+    // `/` truncates towards zero, which only rounds up for a negative, inexact quotient
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    /// Every position inside the extent, in no particular order.
+    fn box_positions<const D: usize>(extent: [usize; D]) -> Vec<[usize; D]> {
+        let mut positions = vec![[0; D]];
+        for (axis, &len) in extent.iter().enumerate() {
+            positions = positions
+                .into_iter()
+                .flat_map(|pos| {
+                    (0..len).map(move |v| {
+                        let mut pos = pos;
+                        pos[axis] = v;
+                        pos
+                    })
+                })
+                .collect();
+        }
+        positions
+    }
+
+    /// Brute-force antinode set: checks every cell against every same-frequency pair.
+    fn brute_antinodes<const D: usize, B: Bounds<D>>(
+        antennas: &[Antenna<D>],
+        bounds: &B,
+        resonant: bool,
+    ) -> HashSet<[usize; D]> {
+        let mut antinodes = HashSet::new();
+
+        for cell in box_positions(bounds.extent()) {
+            if !bounds.contains(cell) {
+                continue;
+            }
+            for (i, &(p, pf)) in antennas.iter().enumerate() {
+                for &(q, qf) in &antennas[i + 1..] {
+                    if pf != qf {
+                        continue;
+                    }
+                    let to_p: [isize; D] =
+                        std::array::from_fn(|a| p[a] as isize - cell[a] as isize);
+                    let to_q: [isize; D] =
+                        std::array::from_fn(|a| q[a] as isize - cell[a] as isize);
+                    // The cell must be in line with both antennas
+                    let collinear =
+                        (0..D).all(|a| (0..D).all(|b| to_p[a] * to_q[b] == to_q[a] * to_p[b]));
+                    if !collinear {
+                        continue;
+                    }
+                    let dp: isize = to_p.iter().map(|v| v * v).sum();
+                    let dq: isize = to_q.iter().map(|v| v * v).sum();
+                    // Paired antinodes lie outside the pair, with both antennas on one side;
+                    // the points a third of the way between them do not count
+                    let outside = (0..D).map(|a| to_p[a] * to_q[a]).sum::<isize>() > 0;
+                    if resonant || (outside && (dp == 4 * dq || dq == 4 * dp)) {
+                        antinodes.insert(cell);
+                    }
+                }
+            }
+        }
+
+        antinodes
+    }
+
+    /// Brute-force antinode set over a whole map rectangle.
+    fn brute_map_antinodes(input: &Input, resonant: bool) -> HashSet<[usize; 2]> {
+        let (map, antennas) = input;
+        brute_antinodes(antennas, &BoxBounds::of_map(map), resonant)
+    }
+
+    /// Brute-force k range: scans a window of k values wide enough for the given box.
+    fn brute_k_range<const D: usize>(
+        p: [usize; D],
+        s: [isize; D],
+        extent: [usize; D],
+    ) -> Option<(isize, isize)> {
+        let limit = extent.iter().sum::<usize>() as isize + 1;
+        let ks: Vec<isize> = (-limit..=limit)
+            .filter(|&k| {
+                (0..D).all(|a| {
+                    let v = p[a] as isize + k * s[a];
+                    v >= 0 && v < extent[a] as isize
+                })
+            })
+            .collect();
+        Some((*ks.first()?, *ks.last()?))
+    }
+
+    fn assert_k_range<const D: usize>(p: [usize; D], s: [isize; D], extent: [usize; D]) {
+        let (k_min, k_max) = find_k_range(p, s, extent);
+        let actual = if k_min <= k_max {
+            Some((k_min, k_max))
+        } else {
+            None
+        };
+        assert_eq!(
+            actual,
+            brute_k_range(p, s, extent),
+            "p = {:?}, s = {:?}, extent {:?}",
+            p,
+            s,
+            extent
+        );
+    }
+
+    #[test]
+    fn render_example_part1() {
+        let input = parse(indoc! {
+        "............
+         ........0...
+         .....0......
+         .......0....
+         ....0.......
+         ......A.....
+         ............
+         ............
+         ........A...
+         .........A..
+         ............
+         ............"
+        });
+        let expected = indoc! {
+        "......#....#
+         ...#....0...
+         ....#0....#.
+         ..#....0....
+         ....0....#..
+         .#....A.....
+         ...#........
+         #......#....
+         ........A...
+         .........A..
+         ..........#.
+         ..........#."
+        };
+        let antinodes = AntinodeMap::new(&input.1, &BoxBounds::of_map(&input.0), &Paired);
+        assert_eq!(antinodes.render(&input.0), expected);
+
+        // The top-most A antenna is itself a 0 antinode, so it stays visible in
+        // the rendering, and one position is an antinode of both frequencies
+        assert!(antinodes.by_frequency[&'0'].contains(&[5, 6]));
+        assert_eq!(antinodes.by_frequency[&'0'].len(), 10);
+        assert_eq!(antinodes.by_frequency[&'A'].len(), 5);
+        assert_eq!(
+            antinodes.overlap(),
+            BTreeMap::from([([1, 3], vec!['0', 'A'])])
+        );
+        assert_eq!(antinodes.all().len(), 14);
+    }
+
+    #[test]
+    fn render_example_part2() {
+        let input = parse(indoc! {
+        "............
+         ........0...
+         .....0......
+         .......0....
+         ....0.......
+         ......A.....
+         ............
+         ............
+         ........A...
+         .........A..
+         ............
+         ............"
+        });
+        let expected = indoc! {
+        "##....#....#
+         .#.#....0...
+         ..#.#0....#.
+         ..##...0....
+         ....0....#..
+         .#...#A....#
+         ...#..#.....
+         #....#.#....
+         ..#.....A...
+         ....#....A..
+         .#........#.
+         ...#......##"
+        };
+        assert_eq!(
+            AntinodeMap::new(&input.1, &BoxBounds::of_map(&input.0), &Resonant).render(&input.0),
+            expected
+        );
+    }
+
+    #[test]
+    fn render_single_frequency_examples() {
+        let input = parse(indoc! {
+        "..........
+         ..........
+         ..........
+         ....a.....
+         ........a.
+         .....a....
+         ..........
+         ......A...
+         ..........
+         .........."
+        });
+        let expected = indoc! {
+        "..........
+         ...#......
+         #.........
+         ....a.....
+         ........a.
+         .....a....
+         ..#.......
+         ......A...
+         ..........
+         .........."
+        };
+        let antinodes = AntinodeMap::new(&input.1, &BoxBounds::of_map(&input.0), &Paired);
+        assert_eq!(antinodes.render(&input.0), expected);
+        // A single A antenna has no partner, so it produces nothing
+        assert!(antinodes.by_frequency[&'A'].is_empty());
+        assert_eq!(antinodes.all().len(), 4);
+
+        let input = parse(indoc! {
+        "T.........
+         ...T......
+         .T........
+         ..........
+         ..........
+         ..........
+         ..........
+         ..........
+         ..........
+         .........."
+        });
+        let expected = indoc! {
+        "T....#....
+         ...T......
+         .T....#...
+         .........#
+         ..#.......
+         ..........
+         ...#......
+         ..........
+         ....#.....
+         .........."
+        };
+        let antinodes = AntinodeMap::new(&input.1, &BoxBounds::of_map(&input.0), &Resonant);
+        assert_eq!(antinodes.render(&input.0), expected);
+        assert_eq!(antinodes.all().len(), 9);
+    }
+
+    #[test]
+    fn division_rounding() {
+        for a in -12isize..=12 {
+            for b in (-5isize..=5).filter(|&b| b != 0) {
+                let exact = a as f64 / b as f64;
+                assert_eq!(ceil_div(a, b), exact.ceil() as isize, "ceil {} / {}", a, b);
+                assert_eq!(
+                    floor_div(a, b),
+                    exact.floor() as isize,
+                    "floor {} / {}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn k_range_all_step_signs() {
+        let steps = [
+            (1, 1),
+            (1, -1),
+            (-1, 1),
+            (-1, -1),
+            (2, 3),
+            (2, -3),
+            (-2, 3),
+            (-2, -3),
+        ];
+        for &(sx, sy) in &steps {
+            // Include starting points beyond the map, whose lines may re-enter it
+            for px in 0..10 {
+                for py in 0..8 {
+                    assert_k_range([px, py], [sx, sy], [7, 5]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn k_range_axis_aligned() {
+        let steps = [(0, 1), (0, -1), (0, 2), (1, 0), (-1, 0), (-3, 0)];
+        for &(sx, sy) in &steps {
+            for px in 0..8 {
+                for py in 0..6 {
+                    assert_k_range([px, py], [sx, sy], [6, 4]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn k_range_corner_antennas() {
+        let (rows, cols) = (8, 11);
+        let corners = [(0, 0), (0, cols - 1), (rows - 1, 0), (rows - 1, cols - 1)];
+        let steps = [
+            (1, 1),
+            (1, -1),
+            (-1, 1),
+            (-1, -1),
+            (0, 1),
+            (0, -1),
+            (1, 0),
+            (-1, 0),
+            (3, -2),
+        ];
+        for &(px, py) in &corners {
+            for &(sx, sy) in &steps {
+                assert_k_range([px, py], [sx, sy], [rows, cols]);
+            }
+        }
+        // The whole anti-diagonal is reachable from the top-right corner
+        assert_eq!(find_k_range([0, 4], [1, -1], [5, 5]), (0, 4));
+    }
+
+    #[test]
+    fn corner_antennas_match_brute_force() {
+        let input = parse(indoc! {
+            "a........b
+             .a......b.
+             ..........
+             ..........
+             .c......d.
+             c........d"
+        });
+        assert_eq!(
+            find_antinodes(&input, &Paired),
+            brute_map_antinodes(&input, false)
+        );
+        assert_eq!(
+            find_antinodes(&input, &Resonant),
+            brute_map_antinodes(&input, true)
+        );
+    }
 
     // Test with the provided example
     #[test]
@@ -227,8 +735,13 @@ mod tests {
          ............"
         };
         assert_eq!(day8_part1(&parse(input)), 14);
+        assert_eq!(
+            find_antinodes(&parse(input), &Paired),
+            brute_map_antinodes(&parse(input), false)
+        );
     }
 
+    #[test]
     fn example_part2() {
         let input = indoc! {
         "............
@@ -246,5 +759,175 @@ mod tests {
         };
         let (map, antennas) = parse(input);
         assert_eq!(day8_part2(&(map, antennas)), 34);
+        assert_eq!(
+            find_antinodes(&parse(input), &Resonant),
+            brute_map_antinodes(&parse(input), true)
+        );
+    }
+
+    #[test]
+    fn masked_cells_hold_no_antinodes() {
+        let (map, antennas) = parse(indoc! {
+        "..........
+         ...~......
+         ..........
+         ....a.....
+         ..........
+         .....a....
+         ..........
+         ......~...
+         ~.........
+         ~~........"
+        });
+        let mask = Mask::from_map(&map, '~');
+        let expected = indoc! {
+        "..........
+         ...~......
+         ..........
+         ....a.....
+         ..........
+         .....a....
+         ..........
+         ......~...
+         ~.........
+         ~~........"
+        };
+        // Both part 1 antinodes fall on water
+        let paired = AntinodeMap::new(&antennas, &mask, &Paired);
+        assert!(paired.all().is_empty());
+        assert_eq!(paired.render(&map), expected);
+
+        // The resonant line skips the water cells but still reaches past them
+        let resonant = AntinodeMap::new(&antennas, &mask, &Resonant);
+        let mut all: Vec<_> = resonant.all().into_iter().collect();
+        all.sort();
+        assert_eq!(all, vec![[3, 4], [5, 5], [9, 7]]);
+        assert_eq!(resonant.all(), brute_antinodes(&antennas, &mask, true));
+    }
+
+    #[test]
+    fn masked_map_matches_brute_force() {
+        let (map, antennas) = parse(indoc! {
+        "a..~..b..c
+         ..~~.....~
+         .b...a....
+         ~...c.....
+         ...~~..a.b
+         .c.~......"
+        });
+        let mask = Mask::from_map(&map, '~');
+        for resonant in [false, true] {
+            let antinodes = if resonant {
+                AntinodeMap::new(&antennas, &mask, &Resonant).all()
+            } else {
+                AntinodeMap::new(&antennas, &mask, &Paired).all()
+            };
+            assert!(antinodes.iter().all(|&[r, c]| map[r][c] != '~'));
+            assert_eq!(antinodes, brute_antinodes(&antennas, &mask, resonant));
+        }
+    }
+
+    #[test]
+    fn layered_field() {
+        let (bounds, antennas) = parse_layers(indoc! {
+        "....
+         .a..
+         ....
+
+         ....
+         ....
+         ..a.
+
+         ....
+         ....
+         ...."
+        })
+        .unwrap();
+        assert_eq!(bounds.extent(), [3, 4, 3]);
+        assert_eq!(antennas, vec![([1, 1, 0], 'a'), ([2, 2, 1], 'a')]);
+
+        // The line leaves the box one step beyond either antenna
+        let paired = AntinodeMap::new(&antennas, &bounds, &Paired);
+        assert!(paired.all().is_empty());
+
+        let resonant = AntinodeMap::new(&antennas, &bounds, &Resonant);
+        let mut all: Vec<_> = resonant.all().into_iter().collect();
+        all.sort();
+        assert_eq!(all, vec![[1, 1, 0], [2, 2, 1]]);
+    }
+
+    #[test]
+    fn layered_field_matches_brute_force() {
+        let (bounds, antennas) = parse_layers(indoc! {
+        "A....
+         ..B..
+         .....
+         ....A
+
+         .....
+         .A...
+         ...B.
+         .....
+
+         ..B..
+         .....
+         A....
+         ....B"
+        })
+        .unwrap();
+        assert_eq!(
+            AntinodeMap::new(&antennas, &bounds, &Paired).all(),
+            brute_antinodes(&antennas, &bounds, false)
+        );
+        assert_eq!(
+            AntinodeMap::new(&antennas, &bounds, &Resonant).all(),
+            brute_antinodes(&antennas, &bounds, true)
+        );
+    }
+
+    #[test]
+    fn mismatched_layers_are_rejected() {
+        let err = parse_layers(indoc! {
+        "...
+         .a.
+
+         ....
+         .a.."
+        })
+        .unwrap_err();
+        assert!(err.contains("layer 2"), "{}", err);
+    }
+
+    #[test]
+    fn k_range_in_three_dimensions() {
+        let steps = [(1, -1, 2), (-2, 0, 1), (0, 0, -1), (1, 1, 1)];
+        for &(sx, sy, sz) in &steps {
+            for px in 0..6 {
+                for py in 0..5 {
+                    for pz in 0..4 {
+                        assert_k_range([px, py, pz], [sx, sy, sz], [4, 3, 2]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn antenna_map() -> impl Strategy<Value = String> {
+        (1usize..9, 1usize..9).prop_flat_map(|(rows, cols)| {
+            let cell = prop::sample::select(vec!['.', '.', '.', '.', 'a', 'a', 'B', '0']);
+            prop::collection::vec(prop::collection::vec(cell, cols), rows).prop_map(|map| {
+                let rows: Vec<String> = map.iter().map(|row| row.iter().collect()).collect();
+                rows.join("\n")
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_brute_force(map in antenna_map()) {
+            let input = parse(&map);
+            prop_assert_eq!(day8_part1(&input), brute_map_antinodes(&input, false).len());
+            prop_assert_eq!(day8_part2(&input), brute_map_antinodes(&input, true).len());
+        }
     }
 }
@@ -1,3 +1,4 @@
+use crate::solution::Solution;
 use std::collections::HashMap;
 
 #[aoc_generator(day9)]
@@ -12,8 +13,8 @@ fn parse(input: &str) -> Vec<i32> {
         let length = chars[i].to_digit(10).unwrap() as usize;
 
         if length > 0 {
-            let fill_val = if is_file { file_id as i32 } else { -1 };
-            disk.extend(std::iter::repeat(fill_val).take(length));
+            let fill_val = if is_file { file_id } else { -1 };
+            disk.resize(disk.len() + length, fill_val);
             if is_file {
                 file_id += 1;
             }
@@ -26,28 +27,26 @@ fn parse(input: &str) -> Vec<i32> {
 }
 
 #[aoc(day9, part1)]
-fn day9_part1(disk: &Vec<i32>) -> i64 {
-    let mut disk = disk.clone();
+fn day9_part1(disk: &[i32]) -> i64 {
+    let mut disk = disk.to_vec();
     // Simulate moves exactly as puzzle states:
     // Move the rightmost file block after the leftmost free space until stable.
-    let mut L = 0;
-    let mut R = disk.len().saturating_sub(1);
+    let mut left = 0;
+    let mut right = disk.len().saturating_sub(1);
 
-    while L < R {
-        while L < disk.len() && disk[L] != -1 {
-            L += 1;
+    while left < right {
+        while left < disk.len() && disk[left] != -1 {
+            left += 1;
         }
-        while R > L && (disk[R] == -1 || R <= L) {
-            R -= 1;
+        while right > left && disk[right] == -1 {
+            right -= 1;
         }
 
-        if L < R && disk[L] == -1 && disk[R] != -1 && R > L {
-            disk[L] = disk[R];
-            disk[R] = -1;
-            L += 1;
-            if R > 0 {
-                R -= 1;
-            }
+        if left < right && disk[left] == -1 && disk[right] != -1 {
+            disk[left] = disk[right];
+            disk[right] = -1;
+            left += 1;
+            right = right.saturating_sub(1);
         } else {
             break;
         }
@@ -63,13 +62,13 @@ fn day9_part1(disk: &Vec<i32>) -> i64 {
 }
 
 #[aoc(day9, part2)]
-fn day9_part2(disk: &Vec<i32>) -> i64 {
-    let mut disk = disk.clone();
+fn day9_part2(disk: &[i32]) -> i64 {
+    let mut disk = disk.to_vec();
 
     let files = find_files(&disk);
     let mut file_list: Vec<(i32, usize, usize)> =
         files.iter().map(|(&fid, &(s, e))| (fid, s, e)).collect();
-    file_list.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+    file_list.sort_unstable_by_key(|&(fid, _, _)| std::cmp::Reverse(fid));
 
     // Get free runs sorted by their start index
     let mut free_runs = find_free_runs(&disk);
@@ -81,12 +80,8 @@ fn day9_part2(disk: &Vec<i32>) -> i64 {
         if let Some((run_idx, alloc_start)) = find_leftmost_suitable_run(&free_runs, length, start)
         {
             // Move the file
-            for i in start..=end {
-                disk[i] = -1;
-            }
-            for i in 0..length {
-                disk[alloc_start + i] = fid;
-            }
+            disk[start..=end].fill(-1);
+            disk[alloc_start..alloc_start + length].fill(fid);
 
             // Update the free run
             let (rstart, rend) = free_runs[run_idx];
@@ -120,8 +115,30 @@ fn day9_part2(disk: &Vec<i32>) -> i64 {
     checksum
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    type Input = Vec<i32>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, String> {
+        Ok(parse(input))
+    }
+
+    fn part1(input: &Self::Input) -> i64 {
+        day9_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> i64 {
+        day9_part2(input)
+    }
+}
+
 /// Find all files and their start/end indices
-fn find_files(disk: &Vec<i32>) -> HashMap<i32, (usize, usize)> {
+fn find_files(disk: &[i32]) -> HashMap<i32, (usize, usize)> {
     let mut files: HashMap<i32, (usize, usize)> = HashMap::new();
     for (i, &b) in disk.iter().enumerate() {
         if b >= 0 {
@@ -135,7 +152,7 @@ fn find_files(disk: &Vec<i32>) -> HashMap<i32, (usize, usize)> {
 }
 
 /// Identify all contiguous free intervals (-1)
-fn find_free_runs(disk: &Vec<i32>) -> Vec<(usize, usize)> {
+fn find_free_runs(disk: &[i32]) -> Vec<(usize, usize)> {
     let mut runs = Vec::new();
     let mut in_run = false;
     let mut start = 0;
@@ -187,6 +204,7 @@ fn find_leftmost_suitable_run(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     #[test]
     fn test_example_1() {
         let disk = parse("2333133121414131402");
@@ -200,4 +218,74 @@ mod tests {
         let result = day9_part2(&disk);
         assert_eq!(result, 2858);
     }
+
+    /// Expands the disk map and compacts it one block at a time.
+    fn reference_blocks(disk_map: &str) -> Vec<Option<usize>> {
+        let mut blocks = Vec::new();
+        for (i, len) in disk_map.chars().enumerate() {
+            let id = if i % 2 == 0 { Some(i / 2) } else { None };
+            blocks.resize(blocks.len() + len.to_digit(10).unwrap() as usize, id);
+        }
+        blocks
+    }
+
+    fn reference_checksum(blocks: &[Option<usize>]) -> i64 {
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(pos, id)| (pos * id.unwrap_or(0)) as i64)
+            .sum()
+    }
+
+    fn reference_part1(disk_map: &str) -> i64 {
+        let mut blocks = reference_blocks(disk_map);
+        while let (Some(free), Some(last)) = (
+            blocks.iter().position(Option::is_none),
+            blocks.iter().rposition(Option::is_some),
+        ) {
+            if free > last {
+                break;
+            }
+            blocks.swap(free, last);
+        }
+        reference_checksum(&blocks)
+    }
+
+    /// Moves whole files, highest ID first, into the leftmost gap that fits.
+    fn reference_part2(disk_map: &str) -> i64 {
+        let mut blocks = reference_blocks(disk_map);
+        let files = disk_map.len().div_ceil(2);
+        for id in (0..files).rev() {
+            let start = blocks.iter().position(|&b| b == Some(id)).unwrap();
+            let len = blocks.iter().filter(|&&b| b == Some(id)).count();
+            if let Some(gap) = (0..start).find(|&i| blocks[i..i + len].iter().all(Option::is_none))
+            {
+                for i in 0..len {
+                    blocks.swap(gap + i, start + i);
+                }
+            }
+        }
+        reference_checksum(&blocks)
+    }
+
+    fn disk_map() -> impl Strategy<Value = String> {
+        prop::collection::vec((1u32..10, 0u32..10), 1..12).prop_map(|entries| {
+            let mut map = String::new();
+            for (file, gap) in entries {
+                map.push(char::from_digit(file, 10).unwrap());
+                map.push(char::from_digit(gap, 10).unwrap());
+            }
+            map.pop();
+            map
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(disk_map in disk_map()) {
+            let disk = parse(&disk_map);
+            prop_assert_eq!(day9_part1(&disk), reference_part1(&disk_map));
+            prop_assert_eq!(day9_part2(&disk), reference_part2(&disk_map));
+        }
+    }
 }
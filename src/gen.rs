@@ -0,0 +1,504 @@
+//! Seeded generators for puzzle inputs of arbitrary size, for stress tests and benchmarks.
+//!
+//! Every generator builds its input around a planted structure, so the part 1 answer is
+//! known without running the solution.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// A generated input and its part 1 answer.
+pub struct Generated {
+    pub input: String,
+    pub answer: String,
+}
+
+/// Small deterministic random number generator (SplitMix64).
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `lo..=hi`.
+    pub fn range(&mut self, lo: usize, hi: usize) -> usize {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as usize
+    }
+
+    /// True with probability `num / den`.
+    pub fn chance(&mut self, num: u64, den: u64) -> bool {
+        self.next_u64() % den < num
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.range(0, items.len() - 1)]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i));
+        }
+    }
+}
+
+/// Generates an input for `day`, or `None` if the day has no generator. See the per-day
+/// functions for what `size` means.
+pub fn generate(day: u32, size: usize, seed: u64) -> Option<Generated> {
+    let rng = &mut Rng::new(seed);
+    let generated = match day {
+        1 => day1(rng, size),
+        2 => day2(rng, size),
+        3 => day3(rng, size),
+        4 => day4(rng, size),
+        5 => day5(rng, size),
+        6 => day6(rng, size),
+        7 => day7(rng, size),
+        8 => day8(rng, size),
+        9 => day9(rng, size),
+        10 => day10(rng, size),
+        _ => return None,
+    };
+    Some(generated)
+}
+
+fn grid_to_string(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `size` pairs of location IDs. The right list is the left list shifted by small offsets,
+/// so once both are sorted the total distance is the sum of the offsets.
+pub fn day1(rng: &mut Rng, size: usize) -> Generated {
+    let mut left = Vec::with_capacity(size);
+    let mut right = Vec::with_capacity(size);
+    let mut id = 10000;
+    let mut distance = 0;
+    for _ in 0..size {
+        // Gaps wider than any offset keep both lists in the same order
+        id += rng.range(10, 30);
+        let offset = rng.range(0, 9);
+        left.push(id);
+        right.push(id + offset);
+        distance += offset;
+    }
+    rng.shuffle(&mut left);
+    rng.shuffle(&mut right);
+
+    let mut input = String::new();
+    for (l, r) in left.iter().zip(&right) {
+        writeln!(input, "{}   {}", l, r).unwrap();
+    }
+    Generated {
+        input,
+        answer: distance.to_string(),
+    }
+}
+
+/// `size` reports, about half of them safe; the others have one step that is flat or too
+/// steep.
+pub fn day2(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut safe = 0;
+    for _ in 0..size {
+        let len = rng.range(5, 8);
+        let increasing = rng.chance(1, 2);
+        let is_safe = rng.chance(1, 2);
+        let bad_step = rng.range(0, len - 2);
+        let mut level = if increasing {
+            rng.range(1, 50)
+        } else {
+            rng.range(50, 99)
+        };
+        let mut levels = vec![level];
+        for i in 0..len - 1 {
+            let step = if !is_safe && i == bad_step {
+                rng.pick(&[0, 4, 5, 6])
+            } else {
+                rng.range(1, 3)
+            };
+            level = if increasing {
+                level + step
+            } else {
+                level - step
+            };
+            levels.push(level);
+        }
+        safe += is_safe as usize;
+        let line: Vec<String> = levels.iter().map(usize::to_string).collect();
+        writeln!(input, "{}", line.join(" ")).unwrap();
+    }
+    Generated {
+        input,
+        answer: safe.to_string(),
+    }
+}
+
+/// `size` valid `mul` instructions hidden among corrupted ones and other noise.
+pub fn day3(rng: &mut Rng, size: usize) -> Generated {
+    // None of these contain a valid instruction, nor can they form one with a neighbour
+    const NOISE: [&str; 10] = [
+        "mul(4*",
+        "mul(6,9!",
+        "?(12,34)",
+        "mul ( 2 , 4 )",
+        "mul[3,7]",
+        "mul(1234,5)",
+        "do()",
+        "don't()",
+        "select()",
+        "%&#@)",
+    ];
+    let mut input = String::new();
+    let mut total = 0;
+    for i in 0..size {
+        for _ in 0..rng.range(0, 3) {
+            input.push_str(rng.pick(&NOISE));
+        }
+        let (x, y) = (rng.range(1, 999), rng.range(1, 999));
+        write!(input, "mul({},{})", x, y).unwrap();
+        total += x * y;
+        if i % 50 == 49 {
+            input.push('\n');
+        }
+    }
+    Generated {
+        input,
+        answer: total.to_string(),
+    }
+}
+
+/// A `size` x `size` letter grid. XMAS is planted in random directions inside 5x5 blocks,
+/// and the filler letters are never X, M, A or S.
+pub fn day4(rng: &mut Rng, size: usize) -> Generated {
+    const FILLER: [char; 6] = ['B', 'C', 'E', 'K', 'O', 'Z'];
+    const DIRECTIONS: [(isize, isize); 8] = [
+        (0, 1),
+        (1, 0),
+        (1, 1),
+        (1, -1),
+        (0, -1),
+        (-1, 0),
+        (-1, -1),
+        (-1, 1),
+    ];
+    let mut grid: Vec<Vec<char>> = (0..size)
+        .map(|_| (0..size).map(|_| rng.pick(&FILLER)).collect())
+        .collect();
+
+    // Each word fits in the 4x4 corner of its block, so the filler row and column between
+    // blocks keep words from running into each other
+    let mut words = 0;
+    for block_r in (0..size.saturating_sub(3)).step_by(5) {
+        for block_c in (0..size.saturating_sub(3)).step_by(5) {
+            if !rng.chance(2, 3) {
+                continue;
+            }
+            let (dr, dc) = rng.pick(&DIRECTIONS);
+            // Start where the word still fits: the far edge when going backwards, anywhere
+            // along an axis it does not move on
+            let mut start = |block: usize, d: isize| match d {
+                -1 => block as isize + 3,
+                0 => (block + rng.range(0, 3)) as isize,
+                _ => block as isize,
+            };
+            let (r, c) = (start(block_r, dr), start(block_c, dc));
+            for (i, ch) in "XMAS".chars().enumerate() {
+                let i = i as isize;
+                grid[(r + dr * i) as usize][(c + dc * i) as usize] = ch;
+            }
+            words += 1;
+        }
+    }
+    Generated {
+        input: grid_to_string(&grid),
+        answer: words.to_string(),
+    }
+}
+
+/// A complete ordering of 49 pages as rules, then `size` updates; about half of them follow
+/// the ordering and the rest have two pages swapped.
+pub fn day5(rng: &mut Rng, size: usize) -> Generated {
+    let mut pages: Vec<usize> = (10..100).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+
+    let mut input = String::new();
+    for (i, a) in pages.iter().enumerate() {
+        for b in &pages[i + 1..] {
+            writeln!(input, "{}|{}", a, b).unwrap();
+        }
+    }
+    input.push('\n');
+
+    let mut total = 0;
+    for _ in 0..size {
+        // Pick pages keeping the order, then maybe swap two of them
+        let len = rng.range(2, 11) * 2 + 1;
+        let mut positions: Vec<usize> = (0..pages.len()).collect();
+        rng.shuffle(&mut positions);
+        positions.truncate(len);
+        positions.sort_unstable();
+        let mut update: Vec<usize> = positions.iter().map(|&p| pages[p]).collect();
+        if rng.chance(1, 2) {
+            total += update[len / 2];
+        } else {
+            let i = rng.range(0, len - 2);
+            let j = rng.range(i + 1, len - 1);
+            update.swap(i, j);
+        }
+        let line: Vec<String> = update.iter().map(usize::to_string).collect();
+        writeln!(input, "{}", line.join(",")).unwrap();
+    }
+    Generated {
+        input,
+        answer: total.to_string(),
+    }
+}
+
+/// A `size` x `size` lab (at least 8 x 8) where obstacles steer the guard along an inward
+/// spiral before it walks out of the top. Other obstacles are scattered off the path.
+pub fn day6(rng: &mut Rng, size: usize) -> Generated {
+    let n = size.max(8);
+    let mut grid = vec![vec!['.'; n]; n];
+    let mut path = HashSet::new();
+    let mut obstacles = Vec::new();
+
+    let (mut top, mut left) = (rng.range(1, 3), rng.range(1, 3));
+    let (mut bottom, mut right) = (n - 1 - rng.range(1, 3), n - 1 - rng.range(1, 3));
+    let start = (bottom, left);
+    let mut pos = start;
+    path.insert(start);
+    let mut walk = |pos: &mut (usize, usize), to: (usize, usize)| {
+        let toward = |from: usize, to: usize| match to.cmp(&from) {
+            Ordering::Greater => from + 1,
+            Ordering::Less => from - 1,
+            Ordering::Equal => from,
+        };
+        while *pos != to {
+            *pos = (toward(pos.0, to.0), toward(pos.1, to.1));
+            path.insert(*pos);
+        }
+    };
+
+    // One lap of the spiral per iteration, each lap two cells further in. Every obstacle
+    // sits on a cell the guard never visits.
+    while right >= left + 4 && bottom >= top + 4 {
+        walk(&mut pos, (top, left));
+        obstacles.push((top - 1, left));
+        walk(&mut pos, (top, right));
+        obstacles.push((top, right + 1));
+        walk(&mut pos, (bottom, right));
+        obstacles.push((bottom + 1, right));
+        walk(&mut pos, (bottom, left + 2));
+        obstacles.push((bottom, left + 1));
+        top += 2;
+        bottom -= 2;
+        left += 2;
+        right -= 2;
+    }
+    let exit = (0, pos.1);
+    walk(&mut pos, exit);
+
+    for (r, row) in grid.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if !path.contains(&(r, c)) && rng.chance(1, 10) {
+                *cell = '#';
+            }
+        }
+    }
+    for (r, c) in obstacles {
+        grid[r][c] = '#';
+    }
+    grid[start.0][start.1] = '^';
+    Generated {
+        input: grid_to_string(&grid),
+        answer: path.len().to_string(),
+    }
+}
+
+/// `size` equations of 3 to 8 numbers. Solvable ones evaluate random `+`/`*` operators;
+/// the others target a value that adding and multiplying can never reach.
+pub fn day7(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut total = 0;
+    for _ in 0..size {
+        let numbers: Vec<usize> = (0..rng.range(3, 8)).map(|_| rng.range(1, 20)).collect();
+        let target = if rng.chance(1, 2) {
+            let value = numbers[1..].iter().fold(numbers[0], |acc, &x| {
+                if rng.chance(1, 2) {
+                    acc + x
+                } else {
+                    acc * x
+                }
+            });
+            total += value;
+            value
+        } else {
+            // Left to right, every result r satisfies r + 1 <= product of (x + 1)
+            numbers.iter().map(|x| x + 1).product()
+        };
+        let line: Vec<String> = numbers.iter().map(usize::to_string).collect();
+        writeln!(input, "{}: {}", target, line.join(" ")).unwrap();
+    }
+    Generated {
+        input,
+        answer: total.to_string(),
+    }
+}
+
+/// A `size` x `size` map with roughly one antenna per 25 cells.
+pub fn day8(rng: &mut Rng, size: usize) -> Generated {
+    let frequencies: Vec<char> = ('0'..='9').chain('a'..='z').chain('A'..='Z').collect();
+    let mut grid = vec![vec!['.'; size]; size];
+    let mut by_frequency = vec![Vec::new(); frequencies.len()];
+    for (r, row) in grid.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if rng.chance(1, 25) {
+                let f = rng.range(0, frequencies.len() - 1);
+                *cell = frequencies[f];
+                by_frequency[f].push((r as isize, c as isize));
+            }
+        }
+    }
+
+    let mut antinodes = HashSet::new();
+    for antennas in &by_frequency {
+        for a in antennas {
+            for b in antennas {
+                let node = (2 * b.0 - a.0, 2 * b.1 - a.1);
+                let inside =
+                    (0..size as isize).contains(&node.0) && (0..size as isize).contains(&node.1);
+                if a != b && inside {
+                    antinodes.insert(node);
+                }
+            }
+        }
+    }
+    Generated {
+        input: grid_to_string(&grid),
+        answer: antinodes.len().to_string(),
+    }
+}
+
+/// A disk map of `size` files with random lengths and gaps.
+pub fn day9(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut blocks: Vec<Option<usize>> = Vec::new();
+    for id in 0..size {
+        let len = rng.range(1, 9);
+        input.push(char::from_digit(len as u32, 10).unwrap());
+        blocks.resize(blocks.len() + len, Some(id));
+        if id + 1 < size {
+            let gap = rng.range(0, 9);
+            input.push(char::from_digit(gap as u32, 10).unwrap());
+            blocks.resize(blocks.len() + gap, None);
+        }
+    }
+
+    // Move file blocks from the end into the leftmost gaps
+    let (mut i, mut j) = (0, blocks.len());
+    while i < j {
+        if blocks[i].is_some() {
+            i += 1;
+        } else if blocks[j - 1].is_none() {
+            j -= 1;
+        } else {
+            blocks.swap(i, j - 1);
+        }
+    }
+    let checksum: usize = blocks
+        .iter()
+        .enumerate()
+        .map(|(pos, id)| pos * id.unwrap_or(0))
+        .sum();
+    Generated {
+        input,
+        answer: checksum.to_string(),
+    }
+}
+
+/// A `size` x `size` map (at least 19 x 19) of impassable ground with hills. Each hill is a
+/// diamond with its summit in the centre, so every cell on its rim is a trailhead of score 1.
+pub fn day10(rng: &mut Rng, size: usize) -> Generated {
+    let n = size.max(19);
+    let mut grid = vec![vec!['.'; n]; n];
+    let mut score = 0;
+    // Hills sit in 21 x 21 blocks, so neighbouring rims only ever touch each other
+    for block_r in (0..=n - 19).step_by(21) {
+        for block_c in (0..=n - 19).step_by(21) {
+            if !rng.chance(3, 4) {
+                continue;
+            }
+            let margin = |rng: &mut Rng, room: usize| rng.range(0, room.min(2));
+            let peak = (
+                block_r + 9 + margin(rng, n - 19 - block_r),
+                block_c + 9 + margin(rng, n - 19 - block_c),
+            );
+            for dr in 0..19_usize {
+                for dc in 0..19_usize {
+                    let distance = dr.abs_diff(9) + dc.abs_diff(9);
+                    if distance <= 9 {
+                        grid[peak.0 + dr - 9][peak.1 + dc - 9] =
+                            char::from_digit(9 - distance as u32, 10).unwrap();
+                    }
+                }
+            }
+            score += 36;
+        }
+    }
+    Generated {
+        input: grid_to_string(&grid),
+        answer: score.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::SOLUTIONS;
+
+    #[test]
+    fn planted_answers() {
+        for entry in SOLUTIONS {
+            for seed in 0..5 {
+                for size in [1, 10, 57, 120] {
+                    let Some(generated) = generate(entry.day, size, seed) else {
+                        continue;
+                    };
+                    assert_eq!(
+                        entry.solve(1, generated.input.trim_end_matches('\n')),
+                        Ok(generated.answer),
+                        "day {}, size {}, seed {}",
+                        entry.day,
+                        size,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(
+            generate(5, 20, 7).unwrap().input,
+            generate(5, 20, 7).unwrap().input
+        );
+        assert_ne!(
+            generate(5, 20, 7).unwrap().input,
+            generate(5, 20, 8).unwrap().input
+        );
+        assert!(generate(11, 20, 7).is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! Reading and moving around rectangular grids addressed by `(row, column)`.
+
+use std::collections::VecDeque;
+
+/// Offsets to the up, right, down and left neighbours, in clockwise order.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Reads a rectangular grid of characters, one row per line.
+pub fn parse_chars(input: &str) -> Result<Vec<Vec<char>>, String> {
+    rectangular(input.lines().map(|line| line.chars().collect()).collect())
+}
+
+/// Checks that every row of a grid is as wide as the first one.
+pub fn rectangular<T>(grid: Vec<Vec<T>>) -> Result<Vec<Vec<T>>, String> {
+    if let Some(first) = grid.first() {
+        let cols = first.len();
+        if let Some((r, row)) = grid.iter().enumerate().find(|(_, row)| row.len() != cols) {
+            return Err(format!(
+                "ragged grid: row {} has {} columns, expected {}",
+                r + 1,
+                row.len(),
+                cols
+            ));
+        }
+    }
+    Ok(grid)
+}
+
+/// The cell offset by `(dr, dc)` from `(r, c)`, if it is inside a grid of `rows` by `cols`.
+pub fn offset(
+    (rows, cols): (usize, usize),
+    (r, c): (usize, usize),
+    (dr, dc): (isize, isize),
+) -> Option<(usize, usize)> {
+    let nr = r.checked_add_signed(dr)?;
+    let nc = c.checked_add_signed(dc)?;
+    (nr < rows && nc < cols).then_some((nr, nc))
+}
+
+/// The cell offset by `d` from `pos`, if it is inside `grid`, whose rows may differ in width.
+pub fn offset_in<T>(
+    grid: &[Vec<T>],
+    pos: (usize, usize),
+    d: (isize, isize),
+) -> Option<(usize, usize)> {
+    let (r, c) = offset((grid.len(), usize::MAX), pos, d)?;
+    (c < grid[r].len()).then_some((r, c))
+}
+
+/// The orthogonal neighbours of a cell that are inside the grid.
+pub fn neighbours(
+    size: (usize, usize),
+    pos: (usize, usize),
+) -> impl Iterator<Item = (usize, usize)> {
+    ORTHOGONAL.iter().filter_map(move |&d| offset(size, pos, d))
+}
+
+/// Number of steps from `start` to every cell reachable through cells where `open` holds.
+pub fn distances(
+    size: (usize, usize),
+    start: (usize, usize),
+    open: impl Fn((usize, usize)) -> bool,
+) -> Vec<Vec<Option<usize>>> {
+    let mut dist = vec![vec![None; size.1]; size.0];
+    if !open(start) {
+        return dist;
+    }
+    dist[start.0][start.1] = Some(0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(pos) = queue.pop_front() {
+        let next_dist = dist[pos.0][pos.1].map(|d| d + 1);
+        for (r, c) in neighbours(size, pos) {
+            if dist[r][c].is_none() && open((r, c)) {
+                dist[r][c] = next_dist;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_grids() {
+        assert_eq!(parse_chars("ab\ncd\n").unwrap(), [['a', 'b'], ['c', 'd']]);
+        assert_eq!(parse_chars("").unwrap(), Vec::<Vec<char>>::new());
+        assert_eq!(
+            parse_chars("ab\nc").unwrap_err(),
+            "ragged grid: row 2 has 1 columns, expected 2"
+        );
+    }
+
+    #[test]
+    fn offsets_stay_inside() {
+        assert_eq!(offset((2, 3), (0, 0), (-1, 0)), None);
+        assert_eq!(offset((2, 3), (1, 2), (0, 1)), None);
+        assert_eq!(offset((2, 3), (1, 2), (-1, -2)), Some((0, 0)));
+        let corner: Vec<_> = neighbours((2, 3), (0, 2)).collect();
+        assert_eq!(corner, [(1, 2), (0, 1)]);
+
+        let ragged = [vec![0; 3], vec![0]];
+        assert_eq!(offset_in(&ragged, (0, 1), (1, 0)), None);
+        assert_eq!(offset_in(&ragged, (0, 0), (1, 0)), Some((1, 0)));
+        assert_eq!(offset_in(&ragged, (1, 0), (-1, 2)), Some((0, 2)));
+    }
+
+    #[test]
+    fn bfs_distances() {
+        let walls = ["..#", ".##", "..."];
+        let open = |(r, c): (usize, usize)| walls[r].as_bytes()[c] == b'.';
+        let dist = distances((3, 3), (0, 0), open);
+        assert_eq!(
+            dist,
+            [
+                [Some(0), Some(1), None],
+                [Some(1), None, None],
+                [Some(2), Some(3), Some(4)],
+            ]
+        );
+        assert!(distances((3, 3), (0, 2), open)
+            .iter()
+            .flatten()
+            .all(Option::is_none));
+    }
+}
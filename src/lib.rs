@@ -4,19 +4,20 @@ extern crate aoc_runner;
 extern crate aoc_runner_derive;
 extern crate crypto;
 
+pub mod answers;
 pub mod day1;
 pub mod day10;
-//pub mod day12;
-//pub mod day11;
-//pub mod day13;
-//pub mod day14;
-//pub mod day15;
-//pub mod day16;
-//pub mod day17;
-//pub mod day18;
-//pub mod day19;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
 pub mod day2;
-//pub mod day20;
+pub mod day20;
 //pub mod day21;
 //pub mod day22;
 //pub mod day23;
@@ -29,5 +30,10 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod gen;
+pub mod grid;
+pub mod path;
+pub mod solution;
+pub mod solvers;
 
 aoc_lib! { year = 2024 }
@@ -1,7 +1,448 @@
 extern crate advent_of_code_2024;
-extern crate aoc_runner;
-extern crate aoc_runner_derive;
 
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2024::answers::{Answers, Check, ANSWERS_PATH};
+use advent_of_code_2024::solution::Entry;
+use advent_of_code_2024::solvers::{day_parts, input_path, solve, Outcome};
+use advent_of_code_2024::YEAR;
+use std::fmt::Write as _;
+use std::io::Read;
+use std::panic;
+use std::process::ExitCode;
+use std::{env, fs, io};
 
-aoc_main! { lib = advent_of_code_2024 }
+const USAGE: &str = "\
+Usage: advent-of-code-2024 [OPTIONS]
+
+Runs the registered solutions, by default every day against input/<year>/day<N>.txt.
+
+Options:
+  -d, --day <N>        Only run day N (repeatable, or a comma-separated list)
+  -p, --part <N>       Only run part N (1 or 2)
+  -i, --input <PATH>   Read the input from PATH instead, or from stdin with `-`
+  -f, --format <FMT>   Output format: text (default), plain or json
+      --verify         Compare the answers against the answers file
+      --record         Store the answers in the answers file
+      --answers <PATH>  Answers file to use (default answers/2024.toml)
+  -h, --help           Print this help
+
+Days without a real input are skipped unless selected with --day. Exits with status 1
+if any input is missing or fails to parse, any solver fails or a verified answer is
+wrong, 2 on bad usage.";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Plain,
+    Json,
+}
+
+struct Options {
+    days: Vec<u32>,
+    part: Option<u32>,
+    input: Option<String>,
+    format: Format,
+    verify: bool,
+    record: bool,
+    answers: String,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let selected: Vec<(u32, u32, &Entry)> = day_parts()
+        .filter(|&(day, part, _)| {
+            (options.days.is_empty() || options.days.contains(&day))
+                && (options.part.is_none() || options.part == Some(part))
+        })
+        .collect();
+    if selected.is_empty() {
+        eprintln!("error: no registered solution matches the selected days and parts");
+        return ExitCode::from(2);
+    }
+
+    // Stdin can only be read once, so it is shared by everything selected
+    let stdin = match options.input.as_deref() {
+        Some("-") => {
+            let mut buffer = String::new();
+            if let Err(e) = io::stdin().read_to_string(&mut buffer) {
+                eprintln!("error: failed to read stdin: {}", e);
+                return ExitCode::FAILURE;
+            }
+            Some(buffer)
+        }
+        _ => None,
+    };
+
+    // Solvers panic on malformed input; report those as failures instead
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut results = Vec::new();
+    for (day, part, entry) in selected {
+        let input = match &stdin {
+            Some(buffer) => Ok(buffer.clone()),
+            None => {
+                let path = options.input.clone().unwrap_or_else(|| input_path(day));
+                read_input(&path, options.days.is_empty())
+            }
+        };
+        let outcome = match input {
+            Ok(input) => solve(entry, part, &input),
+            Err(outcome) => outcome,
+        };
+        results.push((day, part, outcome));
+    }
+
+    print!("{}", report(&results, options.format));
+    let mut failed = results
+        .iter()
+        .any(|(_, _, outcome)| matches!(outcome, Outcome::Failed(..)));
+
+    if options.verify || options.record {
+        let mut answers = match Answers::load(&options.answers) {
+            Ok(answers) => answers,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if options.verify {
+            let summary = verify(&answers, &results);
+            eprint!("{}", summary.0);
+            failed |= summary.1;
+        }
+        if options.record {
+            for (day, part, outcome) in &results {
+                if let Some(answer) = outcome.answer() {
+                    answers.insert(*day, *part, answer);
+                }
+            }
+            if let Err(e) = answers.save(&options.answers) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+            eprintln!("Recorded answers in {}", options.answers);
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Reads an input file. With `skip_missing`, a file that doesn't exist skips the day
+/// instead of failing it.
+fn read_input(path: &str, skip_missing: bool) -> Result<String, Outcome> {
+    fs::read_to_string(path).map_err(|e| {
+        if skip_missing && e.kind() == io::ErrorKind::NotFound {
+            Outcome::Skipped(format!("no input at {}", path))
+        } else {
+            Outcome::Failed("reading", format!("{}: {}", path, e))
+        }
+    })
+}
+
+/// Diffs solved answers against the stored ones, returning the report and whether any
+/// answer is wrong.
+fn verify(answers: &Answers, results: &[(u32, u32, Outcome)]) -> (String, bool) {
+    let mut out = String::new();
+    let (mut correct, mut wrong, mut unknown) = (0, 0, 0);
+    for (day, part, outcome) in results {
+        let Some(answer) = outcome.answer() else {
+            continue;
+        };
+        match answers.check(*day, *part, answer) {
+            Check::Correct => correct += 1,
+            Check::Wrong { expected } => {
+                wrong += 1;
+                writeln!(
+                    out,
+                    "Day {} - Part {}: WRONG, expected {}, got {}",
+                    day, part, expected, answer
+                )
+                .unwrap();
+            }
+            Check::Unknown => {
+                unknown += 1;
+                writeln!(out, "Day {} - Part {}: no stored answer", day, part).unwrap();
+            }
+        }
+    }
+    writeln!(
+        out,
+        "Verified: {} correct, {} wrong, {} unknown",
+        correct, wrong, unknown
+    )
+    .unwrap();
+    (out, wrong > 0)
+}
+
+/// Parses the command line; `None` means help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        days: Vec::new(),
+        part: None,
+        input: None,
+        format: Format::Text,
+        verify: false,
+        record: false,
+        answers: ANSWERS_PATH.to_string(),
+    };
+    while let Some(arg) = args.next() {
+        // Accept both `--day 5` and `--day=5`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-d" | "--day" => {
+                for day in value()?.split(',') {
+                    match day.trim().parse() {
+                        Ok(day @ 1..=25) => options.days.push(day),
+                        _ => return Err(format!("invalid day {:?}", day)),
+                    }
+                }
+            }
+            "-p" | "--part" => match value()?.trim() {
+                "1" => options.part = Some(1),
+                "2" => options.part = Some(2),
+                part => return Err(format!("invalid part {:?}", part)),
+            },
+            "-i" | "--input" => options.input = Some(value()?),
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "plain" => Format::Plain,
+                    "json" => Format::Json,
+                    format => return Err(format!("unknown format {:?}", format)),
+                }
+            }
+            "--verify" => options.verify = true,
+            "--record" => options.record = true,
+            "--answers" => options.answers = value()?,
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+
+    // A single input file only makes sense for a single day
+    if options.input.is_some() && options.days.len() != 1 {
+        return Err("--input needs exactly one --day".to_string());
+    }
+    // Stored answers belong to the real inputs
+    if options.input.is_some() && (options.verify || options.record) {
+        return Err("--verify and --record only work with the real inputs".to_string());
+    }
+    Ok(Some(options))
+}
+
+fn report(results: &[(u32, u32, Outcome)], format: Format) -> String {
+    let mut out = String::new();
+    match format {
+        Format::Text => {
+            writeln!(out, "Advent of code {}", YEAR).unwrap();
+            for (day, part, outcome) in results {
+                match outcome {
+                    Outcome::Solved(answer, generator, runner) => writeln!(
+                        out,
+                        "Day {} - Part {}: {}\n\tgenerator: {:?},\n\trunner: {:?}\n",
+                        day, part, answer, generator, runner
+                    ),
+                    Outcome::Failed(stage, e) => writeln!(
+                        out,
+                        "Day {} - Part {}: FAILED while {}:\n{}\n",
+                        day, part, stage, e
+                    ),
+                    Outcome::Skipped(reason) => {
+                        writeln!(out, "Day {} - Part {}: skipped, {}\n", day, part, reason)
+                    }
+                }
+                .unwrap();
+            }
+        }
+        Format::Plain => {
+            for (_, _, outcome) in results {
+                match outcome {
+                    Outcome::Solved(answer, ..) => writeln!(out, "{}", answer),
+                    Outcome::Failed(..) => writeln!(out, "FAILED"),
+                    Outcome::Skipped(..) => writeln!(out, "SKIPPED"),
+                }
+                .unwrap();
+            }
+        }
+        Format::Json => {
+            let entries: Vec<String> = results
+                .iter()
+                .map(|(day, part, outcome)| match outcome {
+                    Outcome::Solved(answer, generator, runner) => format!(
+                        "{{\"day\":{},\"part\":{},\"answer\":{},\"generator_ns\":{},\"runner_ns\":{}}}",
+                        day,
+                        part,
+                        json_string(answer),
+                        generator.as_nanos(),
+                        runner.as_nanos()
+                    ),
+                    Outcome::Failed(stage, e) => format!(
+                        "{{\"day\":{},\"part\":{},\"error\":{}}}",
+                        day,
+                        part,
+                        json_string(&format!("failed while {}: {}", stage, e))
+                    ),
+                    Outcome::Skipped(reason) => format!(
+                        "{{\"day\":{},\"part\":{},\"skipped\":{}}}",
+                        day,
+                        part,
+                        json_string(reason)
+                    ),
+                })
+                .collect();
+            writeln!(out, "[{}]", entries.join(",")).unwrap();
+        }
+    }
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            ch if (ch as u32) < 0x20 => write!(escaped, "\\u{:04x}", ch as u32).unwrap(),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn args(line: &str) -> Result<Option<Options>, String> {
+        parse_args(line.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parses_selection() {
+        let options = args("--day 5 --part=2 -i - --format json")
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.days, vec![5]);
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input.as_deref(), Some("-"));
+        assert!(options.format == Format::Json);
+
+        let options = args("-d 1,3 -d 7").unwrap().unwrap();
+        assert_eq!(options.days, vec![1, 3, 7]);
+        assert!(args("--help").unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_bad_usage() {
+        assert!(args("--day 26").is_err());
+        assert!(args("--part 3").is_err());
+        assert!(args("--day").is_err());
+        assert!(args("--format xml").is_err());
+        assert!(args("--input day1.txt").is_err());
+        assert!(args("--day 1,2 --input day1.txt").is_err());
+        assert!(args("--day 1 --input day1.txt --verify").is_err());
+    }
+
+    #[test]
+    fn verify_report() {
+        let mut answers = Answers::default();
+        answers.insert(1, 1, "11");
+        answers.insert(1, 2, "31");
+        let results = [
+            (
+                1,
+                1,
+                Outcome::Solved("11".to_string(), Duration::ZERO, Duration::ZERO),
+            ),
+            (
+                1,
+                2,
+                Outcome::Solved("30".to_string(), Duration::ZERO, Duration::ZERO),
+            ),
+            (
+                2,
+                1,
+                Outcome::Solved("2".to_string(), Duration::ZERO, Duration::ZERO),
+            ),
+        ];
+        let (summary, wrong) = verify(&answers, &results);
+        assert!(wrong);
+        assert_eq!(
+            summary,
+            "Day 1 - Part 2: WRONG, expected 31, got 30\n\
+             Day 2 - Part 1: no stored answer\n\
+             Verified: 1 correct, 1 wrong, 1 unknown\n"
+        );
+        assert!(!verify(&answers, &results[..1]).1);
+    }
+
+    #[test]
+    fn missing_inputs() {
+        let path = input_path(26);
+        match read_input(&path, true) {
+            Err(Outcome::Skipped(reason)) => assert_eq!(reason, "no input at input/2024/day26.txt"),
+            _ => panic!("missing input wasn't skipped"),
+        }
+        assert!(matches!(
+            read_input(&path, false),
+            Err(Outcome::Failed("reading", _))
+        ));
+
+        let results = [(26, 1, Outcome::Skipped("no input".to_string()))];
+        assert_eq!(
+            report(&results, Format::Text),
+            "Advent of code 2024\nDay 26 - Part 1: skipped, no input\n\n"
+        );
+        assert_eq!(
+            verify(&Answers::default(), &results).0,
+            "Verified: 0 correct, 0 wrong, 0 unknown\n"
+        );
+    }
+
+    #[test]
+    fn json_report() {
+        let results = [
+            (
+                1,
+                1,
+                Outcome::Solved("11".to_string(), Duration::ZERO, Duration::ZERO),
+            ),
+            (
+                1,
+                2,
+                Outcome::Failed("running", "bad \"input\"".to_string()),
+            ),
+        ];
+        assert_eq!(
+            report(&results, Format::Json),
+            "[{\"day\":1,\"part\":1,\"answer\":\"11\",\"generator_ns\":0,\"runner_ns\":0},\
+             {\"day\":1,\"part\":2,\"error\":\"failed while running: bad \\\"input\\\"\"}]\n"
+        );
+    }
+}
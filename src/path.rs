@@ -0,0 +1,169 @@
+//! Weighted shortest paths over any kind of state, for the maze days.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// The result of [`dijkstra`]: the cheapest cost of every reachable state, and every edge
+/// that lies on some cheapest path.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<S> {
+    costs: HashMap<S, u64>,
+    predecessors: HashMap<S, Vec<S>>,
+}
+
+/// Finds the cheapest paths from the start states to every reachable state.
+///
+/// `neighbours` lists the states one edge away from a state, with the cost of that edge.
+/// Ties are kept: a state reached equally cheaply from several states records them all.
+/// Edge costs are expected to be positive.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> ShortestPaths<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut costs = HashMap::new();
+    let mut predecessors: HashMap<S, Vec<S>> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        costs.insert(start.clone(), 0);
+        queue.push(Reverse((0, start)));
+    }
+
+    while let Some(Reverse((cost, state))) = queue.pop() {
+        // Skip entries superseded by a cheaper one
+        if costs.get(&state).is_some_and(|&best| best < cost) {
+            continue;
+        }
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            match costs.get(&next) {
+                Some(&best) if best < next_cost => {}
+                Some(&best) if best == next_cost => {
+                    predecessors.entry(next).or_default().push(state.clone());
+                }
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![state.clone()]);
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+    }
+
+    ShortestPaths {
+        costs,
+        predecessors,
+    }
+}
+
+impl<S: Clone + Eq + Hash> ShortestPaths<S> {
+    /// Cost of the cheapest path to a state, if it is reachable.
+    pub fn cost(&self, state: &S) -> Option<u64> {
+        self.costs.get(state).copied()
+    }
+
+    /// The states a cheapest path to `state` can come from.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// The cheapest of the given end states, with its cost.
+    pub fn best<'a>(&self, ends: impl IntoIterator<Item = &'a S>) -> Option<(&'a S, u64)>
+    where
+        S: 'a,
+    {
+        ends.into_iter()
+            .filter_map(|end| Some((end, self.cost(end)?)))
+            .min_by_key(|&(_, cost)| cost)
+    }
+
+    /// One cheapest path from a start state to `end`, if it is reachable.
+    pub fn path(&self, end: &S) -> Option<Vec<S>> {
+        self.cost(end)?;
+        let mut path = vec![end.clone()];
+        while let Some(previous) = self.predecessors(path.last().unwrap()).first() {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every state on any cheapest path to whichever of `ends` is cheapest to reach.
+    pub fn on_best_paths<'a>(&self, ends: impl IntoIterator<Item = &'a S>) -> HashSet<S>
+    where
+        S: 'a,
+    {
+        let ends: Vec<&S> = ends.into_iter().collect();
+        let Some((_, best)) = self.best(ends.iter().copied()) else {
+            return HashSet::new();
+        };
+        let mut stack: Vec<S> = ends
+            .into_iter()
+            .filter(|end| self.cost(end) == Some(best))
+            .cloned()
+            .collect();
+        let mut seen: HashSet<S> = stack.iter().cloned().collect();
+        while let Some(state) = stack.pop() {
+            for previous in self.predecessors(&state) {
+                if seen.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A diamond with two equally cheap routes and one dearer one:
+    /// 0 -1-> 1 -1-> 3, 0 -1-> 2 -1-> 3, 0 -5-> 3, 3 -1-> 4.
+    fn diamond(state: &u32) -> Vec<(u32, u64)> {
+        match state {
+            0 => vec![(1, 1), (2, 1), (3, 5)],
+            1 | 2 => vec![(3, 1)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn costs_and_paths() {
+        let paths = dijkstra([0], diamond);
+        assert_eq!(paths.cost(&4), Some(3));
+        assert_eq!(paths.cost(&5), None);
+        assert_eq!(paths.path(&4).unwrap().len(), 4);
+        assert_eq!(paths.path(&0), Some(vec![0]));
+        assert_eq!(paths.best(&[2, 4, 5]), Some((&2, 1)));
+
+        let mut predecessors = paths.predecessors(&3).to_vec();
+        predecessors.sort();
+        assert_eq!(predecessors, [1, 2]);
+    }
+
+    #[test]
+    fn all_best_paths() {
+        let paths = dijkstra([0], diamond);
+        let mut on_path: Vec<u32> = paths.on_best_paths(&[4]).into_iter().collect();
+        on_path.sort();
+        assert_eq!(on_path, [0, 1, 2, 3, 4]);
+        // Only the cheapest of several ends counts
+        let on_path = paths.on_best_paths(&[1, 4]);
+        assert_eq!(on_path, HashSet::from([0, 1]));
+        assert!(paths.on_best_paths(&[7]).is_empty());
+    }
+
+    #[test]
+    fn several_starts() {
+        let paths = dijkstra([3, 0], diamond);
+        assert_eq!(paths.cost(&4), Some(1));
+        assert_eq!(paths.cost(&3), Some(0));
+        assert_eq!(paths.path(&4), Some(vec![3, 4]));
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+/// A day's puzzle: parses the input once and solves both parts from it.
+///
+/// Each `dayN` module implements this on a `DayN` unit struct by calling the same functions
+/// registered with `#[aoc_generator]` and `#[aoc]`.
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, String>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+}
+
+/// Parsed input of some registered day, ready to solve either part.
+pub trait Parsed {
+    fn part1(&self) -> String;
+    fn part2(&self) -> String;
+
+    /// Solves part 1 or 2.
+    fn solve(&self, part: u32) -> String {
+        match part {
+            1 => self.part1(),
+            2 => self.part2(),
+            _ => panic!("no part {}", part),
+        }
+    }
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn part1(&self) -> String {
+        S::part1(&self.0).to_string()
+    }
+
+    fn part2(&self) -> String {
+        S::part2(&self.0).to_string()
+    }
+}
+
+/// A [`Solution`] with its types erased, so days can be enumerated at runtime.
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub day: u32,
+    parse: fn(&str) -> Result<Box<dyn Parsed>, String>,
+}
+
+impl Entry {
+    pub const fn of<S: Solution + 'static>() -> Self {
+        Entry {
+            day: S::DAY,
+            parse: parse_boxed::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, String> {
+        (self.parse)(input)
+    }
+
+    /// Parses the input and solves one part.
+    pub fn solve(&self, part: u32, input: &str) -> Result<String, String> {
+        Ok(self.parse(input)?.solve(part))
+    }
+}
+
+fn parse_boxed<S: Solution + 'static>(input: &str) -> Result<Box<dyn Parsed>, String> {
+    Ok(Box::new(ParsedInput::<S>(S::parse(input)?)))
+}
+
+/// Every implemented day, in order.
+pub const SOLUTIONS: &[Entry] = &[
+    Entry::of::<crate::day1::Day1>(),
+    Entry::of::<crate::day2::Day2>(),
+    Entry::of::<crate::day3::Day3>(),
+    Entry::of::<crate::day4::Day4>(),
+    Entry::of::<crate::day5::Day5>(),
+    Entry::of::<crate::day6::Day6>(),
+    Entry::of::<crate::day7::Day7>(),
+    Entry::of::<crate::day8::Day8>(),
+    Entry::of::<crate::day9::Day9>(),
+    Entry::of::<crate::day10::Day10>(),
+    Entry::of::<crate::day11::Day11>(),
+    Entry::of::<crate::day12::Day12>(),
+    Entry::of::<crate::day13::Day13>(),
+    Entry::of::<crate::day14::Day14>(),
+    Entry::of::<crate::day15::Day15>(),
+    Entry::of::<crate::day16::Day16>(),
+    Entry::of::<crate::day17::Day17>(),
+    Entry::of::<crate::day18::Day18>(),
+    Entry::of::<crate::day19::Day19>(),
+    Entry::of::<crate::day20::Day20>(),
+];
+
+/// Looks up a day in [`SOLUTIONS`].
+pub fn solution(day: u32) -> Option<&'static Entry> {
+    SOLUTIONS.iter().find(|entry| entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day7::Day7;
+
+    #[test]
+    fn typed_and_registered() {
+        let input = "190: 10 19\n3267: 81 40 27\n7290: 6 8 6 15";
+        let equations = Day7::parse(input).unwrap();
+        assert_eq!(Day7::part1(&equations), 3457);
+        assert_eq!(Day7::part2(&equations), 10747);
+
+        let entry = solution(7).unwrap();
+        let parsed = entry.parse(input).unwrap();
+        assert_eq!(parsed.part1(), "3457");
+        assert_eq!(parsed.solve(2), "10747");
+        assert_eq!(entry.solve(1, "190: 10 19"), Ok("190".to_string()));
+    }
+
+    #[test]
+    fn registry() {
+        let days: Vec<u32> = SOLUTIONS.iter().map(|entry| entry.day).collect();
+        assert_eq!(days, (1..=20).collect::<Vec<_>>());
+        assert!(solution(26).is_none());
+        assert_eq!(
+            solution(4).unwrap().solve(1, "XMAS\nXM").unwrap_err(),
+            "ragged grid: row 2 has 2 columns, expected 4"
+        );
+    }
+}
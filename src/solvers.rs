@@ -0,0 +1,84 @@
+use crate::solution::{Entry, SOLUTIONS};
+use crate::YEAR;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+/// Every registered day/part, in the order they run.
+pub fn day_parts() -> impl Iterator<Item = (u32, u32, &'static Entry)> {
+    SOLUTIONS
+        .iter()
+        .flat_map(|entry| [1, 2].map(|part| (entry.day, part, entry)))
+}
+
+/// Outcome of one day/part: the answer with generator and runner timings, the stage that
+/// failed and why, or why it wasn't run.
+pub enum Outcome {
+    Solved(String, Duration, Duration),
+    Failed(&'static str, String),
+    Skipped(String),
+}
+
+impl Outcome {
+    pub fn answer(&self) -> Option<&str> {
+        match self {
+            Outcome::Solved(answer, ..) => Some(answer),
+            Outcome::Failed(..) | Outcome::Skipped(..) => None,
+        }
+    }
+}
+
+/// Path of the real puzzle input for a day, relative to the crate root.
+pub fn input_path(day: u32) -> String {
+    format!("input/{}/day{}.txt", YEAR, day)
+}
+
+/// Parses the input and solves one part, timing both stages.
+pub fn solve(entry: &Entry, part: u32, input: &str) -> Outcome {
+    // Inputs are read as files, but solutions expect no trailing newline
+    let input = input.trim_end_matches('\n');
+    let start = Instant::now();
+    let parsed = match catch(|| entry.parse(input)) {
+        Ok(parsed) => parsed,
+        Err(e) => return Outcome::Failed("generating", e),
+    };
+    let generated = Instant::now();
+    match catch(|| Ok(parsed.solve(part))) {
+        Ok(answer) => Outcome::Solved(answer, generated - start, generated.elapsed()),
+        Err(e) => Outcome::Failed("running", e),
+    }
+}
+
+/// Calls `f`, turning both returned errors and panics into an error message.
+fn catch<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => Err(payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::solution;
+
+    #[test]
+    fn reports_parse_failures() {
+        let day = |day| solution(day).unwrap();
+        match solve(day(4), 1, "XMAS\nXM") {
+            Outcome::Failed(stage, e) => {
+                assert_eq!(stage, "generating");
+                assert_eq!(e, "ragged grid: row 2 has 2 columns, expected 4");
+            }
+            _ => panic!("ragged grid parsed"),
+        }
+        assert!(matches!(
+            solve(day(7), 1, "not an equation"),
+            Outcome::Failed("generating", _)
+        ));
+        assert_eq!(solve(day(7), 1, "190: 10 19\n").answer(), Some("190"));
+    }
+}